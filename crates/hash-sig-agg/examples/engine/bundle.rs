@@ -0,0 +1,33 @@
+use crate::engine::univariate::{UnivariateEngine, UnivariateEngineConfig};
+use hash_sig_agg::{
+    air::HashSigAggAir,
+    bundle::{Bundle, BundleError},
+    hash_sig::{F, VerificationInput},
+};
+use p3_commit::{Pcs, PolynomialSpace};
+use p3_uni_stark_ext::{PcsError, Proof, ProvingKey, VerificationError, VerifyingKey};
+
+impl<C: UnivariateEngineConfig> UnivariateEngine<C>
+where
+    <C::Pcs as Pcs<C::Challenge, C::Challenger>>::Domain: PolynomialSpace<Val = F>,
+{
+    pub fn prove_bundle(
+        &self,
+        pk: &ProvingKey,
+        shards: Vec<VerificationInput>,
+    ) -> Bundle<Proof<C>> {
+        Bundle::prove(self.log_blowup(), shards, |prover_inputs| {
+            self.prove(pk, prover_inputs)
+        })
+    }
+
+    pub fn verify_bundle(
+        &self,
+        vk: &VerifyingKey,
+        bundle: &Bundle<Proof<C>>,
+    ) -> Result<(), BundleError<VerificationError<PcsError<C>>>> {
+        bundle.verify(|verifier_inputs, proof| {
+            self.verify::<HashSigAggAir>(vk, verifier_inputs, proof)
+        })
+    }
+}
@@ -1,10 +1,13 @@
 use core::str::FromStr;
 
+pub mod bundle;
 pub mod multilinear;
+pub mod soundness;
 pub mod univariate;
 
 #[derive(Clone, Copy, Debug)]
 pub enum SecurityAssumption {
+    UniqueDecoding,
     JohnsonBound,
     CapacityBound,
 }
@@ -14,6 +17,7 @@ impl FromStr for SecurityAssumption {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
+            "unique-decoding" => Self::UniqueDecoding,
             "johnson-bound" => Self::JohnsonBound,
             "capacity-bound" => Self::CapacityBound,
             _ => unreachable!(),
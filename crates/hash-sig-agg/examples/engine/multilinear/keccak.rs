@@ -18,12 +18,11 @@ pub type MultilinearConfigKeccak = HyperPlonkConfig<Pcs, E, Challenger>;
 impl MultilinearEngineConfig for MultilinearConfigKeccak {
     fn new(
         log_blowup: usize,
+        security_level: usize,
         proof_of_work_bits: usize,
         security_assumption: SecurityAssumption,
     ) -> Self {
         let dft = Dft::default();
-        // FIXME: Set to 128 when higher degree extension field is available.
-        let security_level = 100;
         let byte_hash = ByteHash {};
         let field_hash = FieldHash::new(byte_hash);
         let compress = Compress::new(byte_hash);
@@ -35,6 +34,7 @@ impl MultilinearEngineConfig for MultilinearConfigKeccak {
             merkle_hash: field_hash,
             merkle_compress: compress,
             soundness_type: match security_assumption {
+                SecurityAssumption::UniqueDecoding => p3_whir::SecurityAssumption::UniqueDecoding,
                 SecurityAssumption::JohnsonBound => p3_whir::SecurityAssumption::JohnsonBound,
                 SecurityAssumption::CapacityBound => p3_whir::SecurityAssumption::CapacityBound,
             },
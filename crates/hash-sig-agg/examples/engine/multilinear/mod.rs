@@ -14,6 +14,7 @@ pub mod keccak;
 pub trait MultilinearEngineConfig: HyperPlonkGenericConfig {
     fn new(
         log_blowup: usize,
+        security_level: usize,
         proof_of_work_bits: usize,
         security_assumption: SecurityAssumption,
     ) -> Self;
@@ -30,11 +31,17 @@ where
 {
     pub fn new(
         log_blowup: usize,
+        security_level: usize,
         proof_of_work_bits: usize,
         security_assumption: SecurityAssumption,
     ) -> Self {
         Self {
-            config: C::new(log_blowup, proof_of_work_bits, security_assumption),
+            config: C::new(
+                log_blowup,
+                security_level,
+                proof_of_work_bits,
+                security_assumption,
+            ),
         }
     }
 
@@ -0,0 +1,143 @@
+use crate::engine::SecurityAssumption;
+use core::fmt::{self, Display};
+
+/// Proximity parameter `m` of the Johnson bound list decoding, see [BCIKS20] Theorem 8.3.
+///
+/// [BCIKS20]: https://eprint.iacr.org/2020/654
+const JOHNSON_M: f64 = 3.0;
+
+/// Soundness estimation of the proof system, which takes the minimum of:
+///
+/// - DEEP-ALI error, bounded by the challenge field size, the constraint degree and the list size.
+/// - Commit phase error of PCS, bounded by the challenge field size and the evaluation domain size.
+/// - Query phase error of PCS, bounded by the number of queries plus the proof-of-work bits.
+///
+/// The approximations follow [ethSTARK] section 5 and [Haböck22], which model FRI. WHIR derives
+/// its own number of queries per round from the security level and assumption, so for the
+/// multilinear engine only [`Soundness::check`] applies, as a necessary bound of the challenge
+/// field size.
+///
+/// [ethSTARK]: https://eprint.iacr.org/2021/582
+/// [Haböck22]: https://eprint.iacr.org/2022/1216
+#[derive(Clone, Copy, Debug)]
+pub struct Soundness {
+    pub security_assumption: SecurityAssumption,
+    /// Size of challenge field in bits.
+    pub challenge_field_bits: usize,
+    /// Maximum degree of constraints.
+    pub max_constraint_degree: usize,
+    /// Logarithmic height of the highest trace.
+    pub log_max_height: usize,
+    /// Logarithmic blowup factor (inverse of RS code rate).
+    pub log_blowup: usize,
+    /// Proof-of-work bits ground before the query phase.
+    pub proof_of_work_bits: usize,
+}
+
+impl Soundness {
+    /// Returns the maximum security level in bits reachable with unlimited queries.
+    pub fn max_security_level(&self) -> f64 {
+        self.deep_ali_bits().min(self.commit_phase_bits())
+    }
+
+    /// Returns the minimum number of queries to reach `security_level`, or error if the
+    /// challenge field is too small to reach `security_level` regardless of queries.
+    ///
+    /// At least 1 query is returned even if proof-of-work alone reaches `security_level`, since
+    /// the query phase is what checks proximity at all.
+    pub fn num_queries(&self, security_level: usize) -> Result<usize, InsufficientSecurity> {
+        self.check(security_level)?;
+        let bits = security_level.saturating_sub(self.proof_of_work_bits) as f64;
+        #[allow(clippy::cast_sign_loss)]
+        Ok(((bits / self.bits_per_query()).ceil() as usize).max(1))
+    }
+
+    /// Returns error if the challenge field is too small to reach `security_level`.
+    pub fn check(&self, security_level: usize) -> Result<(), InsufficientSecurity> {
+        let max_security_level = self.max_security_level();
+        if max_security_level < security_level as f64 {
+            return Err(InsufficientSecurity {
+                security_assumption: self.security_assumption,
+                security_level,
+                max_security_level,
+            });
+        }
+        Ok(())
+    }
+
+    fn log_inv_rate(&self) -> f64 {
+        self.log_blowup as f64
+    }
+
+    fn log_domain_size(&self) -> f64 {
+        (self.log_max_height + self.log_blowup) as f64
+    }
+
+    /// Returns the bits of soundness each query contributes, which is `-log2(1 - δ)` where `δ`
+    /// is the proximity parameter the PCS is sound up to.
+    fn bits_per_query(&self) -> f64 {
+        match self.security_assumption {
+            // δ = (1 - ρ) / 2
+            SecurityAssumption::UniqueDecoding => {
+                1.0 - (1.0 + (-self.log_inv_rate()).exp2()).log2()
+            }
+            // δ = 1 - √ρ
+            SecurityAssumption::JohnsonBound => self.log_inv_rate() / 2.0,
+            // δ = 1 - ρ
+            SecurityAssumption::CapacityBound => self.log_inv_rate(),
+        }
+    }
+
+    /// Returns the logarithmic list size of codewords within proximity parameter `δ`.
+    fn log_list_size(&self) -> f64 {
+        match self.security_assumption {
+            SecurityAssumption::UniqueDecoding => 0.0,
+            // L = (m + 1/2) / √ρ
+            SecurityAssumption::JohnsonBound => {
+                (JOHNSON_M + 0.5).log2() + self.log_inv_rate() / 2.0
+            }
+            // L = 1 / ρ
+            SecurityAssumption::CapacityBound => self.log_inv_rate(),
+        }
+    }
+
+    /// Returns `-log2(L · D · |H| / |E|)`.
+    fn deep_ali_bits(&self) -> f64 {
+        self.challenge_field_bits as f64
+            - self.log_list_size()
+            - (self.max_constraint_degree as f64).log2()
+            - self.log_max_height as f64
+    }
+
+    fn commit_phase_bits(&self) -> f64 {
+        match self.security_assumption {
+            // ε = |D| / |E|
+            SecurityAssumption::UniqueDecoding | SecurityAssumption::CapacityBound => {
+                self.challenge_field_bits as f64 - self.log_domain_size()
+            }
+            // ε = (m + 1/2)^7 / (3 · ρ^(3/2)) · |D|^2 / |E|
+            SecurityAssumption::JohnsonBound => {
+                self.challenge_field_bits as f64
+                    - 2.0 * self.log_domain_size()
+                    - (7.0 * (JOHNSON_M + 0.5).log2() - 3f64.log2() + 1.5 * self.log_inv_rate())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct InsufficientSecurity {
+    pub security_assumption: SecurityAssumption,
+    pub security_level: usize,
+    pub max_security_level: f64,
+}
+
+impl Display for InsufficientSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "insufficient soundness, requires {} bits but at most {:.2} bits is reachable under {:?} with the current challenge field",
+            self.security_level, self.max_security_level, self.security_assumption
+        )
+    }
+}
@@ -1,4 +1,3 @@
-use crate::engine::SecurityAssumption;
 use p3_air::{Air, BaseAirWithPublicValues};
 use p3_uni_stark_ext::{
     PcsError, Proof, ProverConstraintFolder, ProverInput, ProverInteractionFolder, ProvingKey,
@@ -13,8 +12,8 @@ pub trait UnivariateEngineConfig: StarkGenericConfig {
     fn new(
         log_blowup: usize,
         log_final_poly_len: usize,
+        num_queries: usize,
         proof_of_work_bits: usize,
-        security_assumption: SecurityAssumption,
     ) -> Self;
 }
 
@@ -27,15 +26,15 @@ impl<C: UnivariateEngineConfig> UnivariateEngine<C> {
     pub fn new(
         log_blowup: usize,
         log_final_poly_len: usize,
+        num_queries: usize,
         proof_of_work_bits: usize,
-        security_assumption: SecurityAssumption,
     ) -> Self {
         Self {
             config: C::new(
                 log_blowup,
                 log_final_poly_len,
+                num_queries,
                 proof_of_work_bits,
-                security_assumption,
             ),
             log_blowup,
         }
@@ -82,16 +81,3 @@ impl<C: UnivariateEngineConfig> UnivariateEngine<C> {
         verify(self.config(), vk, inputs, proof)
     }
 }
-
-pub const fn num_fri_queries(
-    log_blowup: usize,
-    proof_of_work_bits: usize,
-    security_assumption: SecurityAssumption,
-) -> usize {
-    match security_assumption {
-        SecurityAssumption::JohnsonBound => {
-            usize::div_ceil(2 * (128 - proof_of_work_bits), log_blowup)
-        }
-        SecurityAssumption::CapacityBound => usize::div_ceil(128 - proof_of_work_bits, log_blowup),
-    }
-}
@@ -1,7 +1,4 @@
-use crate::engine::{
-    SecurityAssumption,
-    univariate::{UnivariateEngineConfig, num_fri_queries},
-};
+use crate::engine::univariate::UnivariateEngineConfig;
 use hash_sig_agg::hash_sig::{E, F, Poseidon2, RC16, RC24};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
@@ -27,8 +24,8 @@ impl UnivariateEngineConfig for UnivariateConfigPoseidon2 {
     fn new(
         log_blowup: usize,
         log_final_poly_len: usize,
+        num_queries: usize,
         proof_of_work_bits: usize,
-        security_assumption: SecurityAssumption,
     ) -> Self {
         let hash = FieldHash::new(Poseidon2::new(
             ExternalLayerConstants::new(
@@ -50,7 +47,7 @@ impl UnivariateEngineConfig for UnivariateConfigPoseidon2 {
         let fri_config = FriConfig {
             log_blowup,
             log_final_poly_len,
-            num_queries: num_fri_queries(log_blowup, proof_of_work_bits, security_assumption),
+            num_queries,
             proof_of_work_bits,
             arity_bits: 3,
             mmcs: challenge_mmcs,
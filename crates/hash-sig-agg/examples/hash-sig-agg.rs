@@ -2,23 +2,48 @@ use clap::{
     Parser,
     builder::{PossibleValuesParser, RangedU64ValueParser},
 };
-use core::fmt::Debug;
+use core::{
+    fmt::{Debug, Display},
+    ops::Range,
+};
 use engine::{
     multilinear::{MultilinearEngineConfig, MultilnearEngine, keccak::MultilinearConfigKeccak},
+    soundness::Soundness,
     univariate::{
         UnivariateEngine, UnivariateEngineConfig, keccak::UnivariateConfigKeccak,
         poseidon2::UnivariateConfigPoseidon2,
     },
 };
 use hash_sig_agg::{
-    air::{generate_prover_inputs, verifier_inputs},
-    hash_sig::F,
+    air::{
+        AirRegistry, HashSigAggAir, MAX_CONSTRAINT_DEGREE,
+        export::export_traces_csv,
+        generate_prover_inputs, generate_prover_inputs_from_pairs,
+        generate_prover_inputs_with_memory_budget,
+        stats::{AirStats, air_stats},
+        symbolic::write_constraints_json,
+        verifier_inputs,
+    },
+    bundle::split_shards,
+    hash_sig::{
+        E, F, HASH_FE_LEN, MSG_LEN, NUM_CHUNKS, Poseidon2TargetSum, VerificationInput, encode_msg,
+        signer_commitment,
+    },
 };
-use hash_sig_testdata::mock_vi;
+use hash_sig_testdata::{
+    dataset::{self, DatasetReader, Header},
+    mock_vi_with_seed,
+};
+use p3_air_ext::ProverInput;
 use p3_commit::{Pcs, PolynomialSpace};
-use p3_field::TwoAdicField;
+use p3_field::{Field, PrimeCharacteristicRing, TwoAdicField};
 use p3_ml_pcs::MlPcs;
-use std::{process, time::Instant};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process,
+    time::Instant,
+};
 use util::{init_tracing, print_summary};
 
 mod engine;
@@ -29,6 +54,8 @@ static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 #[derive(Clone, Debug, clap::Parser)]
 #[command(version, about)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// PIOP to use to prove the AIR.
     #[arg(long, short = 'i', default_value_t = String::from("univariate"), value_parser = PossibleValuesParser::new(["univariate", "multilinear"]))]
     piop: String,
@@ -43,30 +70,91 @@ struct Args {
     /// Requires 'log-blowup + log-signatures <= 7' when 'piop = multilinear'.
     #[arg(long, short = 'l', verbatim_doc_comment)]
     log_signatures: Option<usize>,
+    /// Amount of disjoint signer shards to aggregate separately into a bundle.
+    /// Only supported when 'piop = univariate'.
+    #[arg(long, short = 'n', default_value_t = 1, value_parser = RangedU64ValueParser::<usize>::new().range(1..), verbatim_doc_comment)]
+    num_shards: usize,
+    /// Memory budget in MiB of intermediate per-signature data during trace generation, on top of
+    /// the traces themselves. Unbounded when not given.
+    #[arg(long, short = 'M', verbatim_doc_comment)]
+    memory_budget: Option<usize>,
+    /// Directory to export traces into as CSV files, one per AIR, instead of proving.
+    #[arg(long, short = 'e')]
+    export_traces: Option<PathBuf>,
+    /// Signature range 'start..end' of rows to export. All rows are exported when not given.
+    #[arg(long, value_parser = parse_range, requires = "export_traces")]
+    export_signatures: Option<Range<usize>>,
+    /// Seed of mock signatures, so inputs are the same between runs.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Dataset file of signatures to load, which is generated with 'seed' and saved if missing.
+    #[arg(long, short = 'd')]
+    dataset: Option<PathBuf>,
+    /// Target security level in bits.
+    /// Defaults to the maximum level reachable with the current challenge field, capped at
+    /// 'MAX_DEFAULT_SECURITY_LEVEL'.
+    #[arg(long, short = 'b', verbatim_doc_comment)]
+    security_level: Option<usize>,
     /// Maximum proof-of-work bits to use.
     #[arg(long, short = 'p', default_value_t = 0)]
     pow_bits: usize,
     /// Security assumption of PCS to use.
-    #[arg(long, short = 's', default_value_t = String::from("johnson-bound"), value_parser = PossibleValuesParser::new(["johnson-bound", "capacity-bound"]))]
+    #[arg(long, short = 's', default_value_t = String::from("johnson-bound"), value_parser = PossibleValuesParser::new(["unique-decoding", "johnson-bound", "capacity-bound"]))]
     security_assumption: String,
 }
 
+#[derive(Clone, Debug, clap::Subcommand)]
+enum Command {
+    /// Print width, height, constraints and interactions of each AIR for 'log-signatures', and
+    /// the total committed cells, instead of proving.
+    Stats,
+    /// Print constraints and interactions of each AIR as JSON of symbolic expression trees with
+    /// named columns, instead of proving.
+    Constraints,
+}
+
+// `Chain` has 117 rows per sig
+const LOG_MAX_ROWS_PER_SIG: usize = 7;
+
+const MAX_DEFAULT_SECURITY_LEVEL: usize = 128;
+
+fn parse_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("expected 'start..end' but got '{s}'"))?;
+    let start = start
+        .parse()
+        .map_err(|err| format!("invalid start: {err}"))?;
+    let end = end.parse().map_err(|err| format!("invalid end: {err}"))?;
+    if start > end {
+        return Err(format!("start {start} is greater than end {end}"));
+    }
+    Ok(start..end)
+}
+
 fn max_log_signatures(piop: &str) -> usize {
     match piop {
-        // `Chain` has 117 rows per sig
-        "univariate" => F::TWO_ADICITY - 7,
-        // `Chain` has 117 rows per sig, `Chain` and `MerkleTree` have >512 columns.
-        "multilinear" => F::TWO_ADICITY - 7 - 10,
+        "univariate" => F::TWO_ADICITY - LOG_MAX_ROWS_PER_SIG,
+        // `Chain` and `MerkleTree` have >512 columns.
+        "multilinear" => F::TWO_ADICITY - LOG_MAX_ROWS_PER_SIG - 10,
         _ => unreachable!(),
     }
 }
 
 fn main() {
     let Args {
+        command,
         piop,
         pcs_merkle_hash,
         log_blowup,
         log_signatures,
+        num_shards,
+        memory_budget,
+        export_traces,
+        export_signatures,
+        seed,
+        dataset,
+        security_level,
         pow_bits,
         security_assumption,
     }: Args = Parser::parse();
@@ -80,45 +168,108 @@ fn main() {
             );
             process::exit(2)
         }
+        ("multilinear", _) if num_shards > 1 => {
+            eprintln!("error: sharding is only supported when 'piop = univariate'");
+            process::exit(2)
+        }
         ("univariate", log_signatures) => log_signatures.unwrap_or(13),
         ("multilinear", log_signatures) => log_signatures.unwrap_or(6),
         _ => unreachable!(),
     };
+
+    match command {
+        Some(Command::Stats) => return print_air_stats(log_signatures, log_blowup),
+        Some(Command::Constraints) => {
+            let airs = verifier_inputs(0, [0; MSG_LEN], [F::ZERO; HASH_FE_LEN])
+                .iter()
+                .map(|input| input.air().clone())
+                .collect::<Vec<_>>();
+            return write_constraints_json(io::stdout().lock(), &airs).unwrap();
+        }
+        None => {}
+    }
+
+    if let Some(dir) = export_traces {
+        let signatures = Signatures::new(1 << log_signatures, seed, dataset.as_deref());
+        let prover_inputs = signatures.generate_prover_inputs(0, memory_budget);
+        if let Err(err) = export_traces_csv(&dir, &prover_inputs, export_signatures.as_ref()) {
+            eprintln!(
+                "error: failed to export traces into {}: {err}",
+                dir.display()
+            );
+            process::exit(1)
+        }
+        return;
+    }
+
     let security_assumption = security_assumption.parse().unwrap();
+    let soundness = Soundness {
+        security_assumption,
+        challenge_field_bits: E::bits(),
+        max_constraint_degree: MAX_CONSTRAINT_DEGREE,
+        log_max_height: log_signatures + LOG_MAX_ROWS_PER_SIG,
+        log_blowup,
+        proof_of_work_bits: pow_bits,
+    };
+    #[allow(clippy::cast_sign_loss)]
+    let security_level = security_level.unwrap_or_else(|| {
+        (soundness.max_security_level().floor().max(0.0) as usize).min(MAX_DEFAULT_SECURITY_LEVEL)
+    });
+    if let Err(err) = soundness.check(security_level) {
+        eprintln!("error: {err}");
+        process::exit(2)
+    }
+    println!("security level: {security_level} bits ({security_assumption:?})");
 
+    let signatures = Signatures::new(1 << log_signatures, seed, dataset.as_deref());
     match piop.as_str() {
         "univariate" => {
             let log_final_poly_len = log_signatures.saturating_sub(1).min(3);
+            let num_queries = soundness.num_queries(security_level).unwrap();
             match pcs_merkle_hash.as_str() {
                 "keccak" => {
                     let engine = UnivariateEngine::<UnivariateConfigKeccak>::new(
                         log_blowup,
                         log_final_poly_len,
+                        num_queries,
                         pow_bits,
-                        security_assumption,
                     );
-                    run_univariate(&engine, log_signatures);
+                    run_univariate(
+                        &engine,
+                        log_signatures,
+                        num_shards,
+                        memory_budget,
+                        signatures,
+                    );
                 }
                 "poseidon2" => {
                     let engine = UnivariateEngine::<UnivariateConfigPoseidon2>::new(
                         log_blowup,
                         log_final_poly_len,
+                        num_queries,
                         pow_bits,
-                        security_assumption,
                     );
-                    run_univariate(&engine, log_signatures);
+                    run_univariate(
+                        &engine,
+                        log_signatures,
+                        num_shards,
+                        memory_budget,
+                        signatures,
+                    );
                 }
                 _ => unreachable!(),
             }
         }
+        // WHIR derives its queries from `security_level` itself, see `Soundness`.
         "multilinear" => match pcs_merkle_hash.as_str() {
             "keccak" => {
                 let engine = MultilnearEngine::<MultilinearConfigKeccak>::new(
                     log_blowup,
+                    security_level,
                     pow_bits,
                     security_assumption,
                 );
-                run_multilinear(&engine, log_signatures);
+                run_multilinear(&engine, log_signatures, memory_budget, &signatures);
             }
             "poseidon2" => unimplemented!(),
             _ => unreachable!(),
@@ -127,26 +278,169 @@ fn main() {
     }
 }
 
-fn run_univariate<C: UnivariateEngineConfig>(engine: &UnivariateEngine<C>, log_signatures: usize)
-where
+fn print_air_stats(log_signatures: usize, log_blowup: usize) {
+    let stats = air_stats(1 << log_signatures);
+    println!(
+        "{:<16}{:>8}{:>8}{:>13}{:>8}{:>14}{:>14}",
+        "AIR", "width", "height", "constraints", "degree", "interactions", "cells"
+    );
+    for stats in &stats {
+        let height = format!("2^{}", stats.height.ilog2());
+        println!(
+            "{:<16}{:>8}{height:>8}{:>13}{:>8}{:>14}{:>14}",
+            stats.name,
+            stats.width,
+            stats.num_constraints,
+            stats.max_constraint_degree,
+            stats.num_interactions,
+            stats.cells(),
+        );
+    }
+    let cells = stats.iter().map(AirStats::cells).sum::<usize>();
+    println!(
+        "total committed cells: {cells} in main traces, {} after blowup",
+        cells << log_blowup
+    );
+}
+
+/// Signatures to aggregate, which are either mocked in memory, or streamed from a dataset file
+/// every time traces are generated.
+enum Signatures {
+    Mock(VerificationInput),
+    Dataset(PathBuf, Header),
+}
+
+impl Signatures {
+    /// Returns mock signatures of `seed`, or signatures of `dataset`, which is saved first if
+    /// missing.
+    fn new(num_sigs: usize, seed: u64, dataset: Option<&Path>) -> Self {
+        let Some(path) = dataset else {
+            return Self::Mock(mock_vi_with_seed(num_sigs, seed));
+        };
+        if !path.exists() {
+            let vi = mock_vi_with_seed(num_sigs, seed);
+            if let Err(err) = dataset::save(path, &vi, false) {
+                eprintln!("error: failed to save dataset {}: {err}", path.display());
+                process::exit(1)
+            }
+            return Self::Mock(vi);
+        }
+        let header = open_dataset(path).header().clone();
+        if header.count != num_sigs as u64 {
+            eprintln!(
+                "error: dataset {} has {} signatures but expected {num_sigs}",
+                path.display(),
+                header.count
+            );
+            process::exit(2)
+        }
+        Self::Dataset(path.to_path_buf(), header)
+    }
+
+    const fn epoch_and_msg(&self) -> (u32, [u8; MSG_LEN]) {
+        match self {
+            Self::Mock(vi) => (vi.epoch, vi.msg),
+            Self::Dataset(_, header) => (header.epoch, header.msg),
+        }
+    }
+
+    /// Returns commitment of signers in order, where signers of dataset are streamed.
+    fn signer_commitment(&self) -> [F; HASH_FE_LEN] {
+        match self {
+            Self::Mock(vi) => signer_commitment(vi.pairs.iter().map(|(pk, _)| pk)),
+            Self::Dataset(path, _) => {
+                let pks = open_dataset(path)
+                    .map(|pair| {
+                        pair.unwrap_or_else(|err| exit_load_dataset_failed(path, &err))
+                            .0
+                    })
+                    .collect::<Vec<_>>();
+                signer_commitment(&pks)
+            }
+        }
+    }
+
+    /// Loads all signatures at once.
+    fn into_vi(self) -> VerificationInput {
+        match self {
+            Self::Mock(vi) => vi,
+            Self::Dataset(path, _) => open_dataset(&path)
+                .into_vi()
+                .unwrap_or_else(|err| exit_load_dataset_failed(&path, &err)),
+        }
+    }
+
+    fn generate_prover_inputs(
+        &self,
+        extra_capacity_bits: usize,
+        memory_budget: Option<usize>,
+    ) -> Vec<ProverInput<F, HashSigAggAir>> {
+        match (self, memory_budget) {
+            (Self::Mock(vi), Some(memory_budget)) => generate_prover_inputs_with_memory_budget(
+                extra_capacity_bits,
+                vi.clone(),
+                memory_budget << 20,
+            ),
+            (Self::Mock(vi), None) => generate_prover_inputs(extra_capacity_bits, vi.clone()),
+            (Self::Dataset(path, header), memory_budget) => {
+                let pairs = open_dataset(path)
+                    .map(|pair| pair.unwrap_or_else(|err| exit_load_dataset_failed(path, &err)));
+                generate_prover_inputs_from_pairs(
+                    &AirRegistry::builtin(header.epoch, encode_msg(header.msg), &[]),
+                    extra_capacity_bits,
+                    header.epoch,
+                    header.msg,
+                    header.count as usize,
+                    pairs,
+                    memory_budget.map_or(usize::MAX, |memory_budget| memory_budget << 20),
+                )
+                .unwrap_or_else(|err| exit_load_dataset_failed(path, &err))
+            }
+        }
+    }
+}
+
+fn open_dataset(path: &Path) -> DatasetReader<Poseidon2TargetSum, NUM_CHUNKS> {
+    DatasetReader::open(path).unwrap_or_else(|err| exit_load_dataset_failed(path, &err))
+}
+
+fn exit_load_dataset_failed(path: &Path, err: &impl Display) -> ! {
+    eprintln!("error: failed to load dataset {}: {err}", path.display());
+    process::exit(1)
+}
+
+fn run_univariate<C: UnivariateEngineConfig>(
+    engine: &UnivariateEngine<C>,
+    log_signatures: usize,
+    num_shards: usize,
+    memory_budget: Option<usize>,
+    signatures: Signatures,
+) where
     <C::Pcs as Pcs<C::Challenge, C::Challenger>>::Domain: PolynomialSpace<Val = F>,
 {
-    let vi = mock_vi(1 << log_signatures);
-    let verifier_inputs = verifier_inputs(vi.epoch, vi.msg);
+    if num_shards > 1 {
+        return run_univariate_bundle(engine, log_signatures, num_shards, signatures.into_vi());
+    }
+
+    let (epoch, msg) = signatures.epoch_and_msg();
+    let verifier_inputs = verifier_inputs(epoch, msg, signatures.signer_commitment());
     let (vk, pk) = engine.keygen(&verifier_inputs);
 
     // Warm up
     {
         let start = Instant::now();
         while Instant::now().duration_since(start).as_secs() < 3 {
-            engine.prove(&pk, generate_prover_inputs(engine.log_blowup(), vi.clone()));
+            engine.prove(
+                &pk,
+                signatures.generate_prover_inputs(engine.log_blowup(), memory_budget),
+            );
         }
     }
 
     let tracing_processor = init_tracing();
 
     let start = Instant::now();
-    let prover_inputs = generate_prover_inputs(engine.log_blowup(), vi);
+    let prover_inputs = signatures.generate_prover_inputs(engine.log_blowup(), memory_budget);
     let proof = engine.prove(&pk, prover_inputs);
     let proving_time = start.elapsed();
     let proving_time_components = tracing_processor.format_by_components(
@@ -175,26 +469,56 @@ where
     );
 }
 
-fn run_multilinear<C: MultilinearEngineConfig>(engine: &MultilnearEngine<C>, log_signatures: usize)
-where
+fn run_univariate_bundle<C: UnivariateEngineConfig>(
+    engine: &UnivariateEngine<C>,
+    log_signatures: usize,
+    num_shards: usize,
+    vi: VerificationInput,
+) where
+    <C::Pcs as Pcs<C::Challenge, C::Challenger>>::Domain: PolynomialSpace<Val = F>,
+{
+    // Signer commitment of each shard is only known to `verify_bundle`, and keygen only takes AIRs.
+    let verifier_inputs = verifier_inputs(vi.epoch, vi.msg, [F::ZERO; HASH_FE_LEN]);
+    let (vk, pk) = engine.keygen(&verifier_inputs);
+    let shards = split_shards(vi, num_shards);
+
+    let start = Instant::now();
+    let bundle = engine.prove_bundle(&pk, shards);
+    let proving_time = start.elapsed();
+
+    let start = Instant::now();
+    engine
+        .verify_bundle(&vk, &bundle)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let verifying_time = start.elapsed();
+
+    print_summary(log_signatures, proving_time, &bundle, verifying_time, None);
+}
+
+fn run_multilinear<C: MultilinearEngineConfig>(
+    engine: &MultilnearEngine<C>,
+    log_signatures: usize,
+    memory_budget: Option<usize>,
+    signatures: &Signatures,
+) where
     C::Pcs: MlPcs<C::Challenge, C::Challenger, Val = F>,
 {
-    let vi = mock_vi(1 << log_signatures);
-    let verifier_inputs = verifier_inputs(vi.epoch, vi.msg);
+    let (epoch, msg) = signatures.epoch_and_msg();
+    let verifier_inputs = verifier_inputs(epoch, msg, signatures.signer_commitment());
     let (vk, pk) = engine.keygen(&verifier_inputs);
 
     // Warm up
     {
         let start = Instant::now();
         while Instant::now().duration_since(start).as_secs() < 3 {
-            engine.prove(&pk, generate_prover_inputs(0, vi.clone()));
+            engine.prove(&pk, signatures.generate_prover_inputs(0, memory_budget));
         }
     }
 
     let tracing_processor = init_tracing();
 
     let start = Instant::now();
-    let prover_inputs = generate_prover_inputs(0, vi);
+    let prover_inputs = signatures.generate_prover_inputs(0, memory_budget);
     let proof = engine.prove(&pk, prover_inputs);
     let proving_time = start.elapsed();
     let proving_time_components = tracing_processor.format_by_components(
@@ -0,0 +1,343 @@
+use crate::hash_sig::{HASH_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN};
+use core::iter;
+use p3_air_ext::InteractionBuilder;
+
+/// Buses shared by all AIRs, which AIRs registered in [`AirRegistry`](crate::air::AirRegistry)
+/// can also send to or receive from.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bus {
+    Parameter,
+    MerkleRootAndMsgHash,
+    Chain,
+    MerkleLeaf,
+    Decomposition,
+    RangeCheck,
+}
+
+impl Bus {
+    pub const ALL: [Self; 6] = [
+        Self::Parameter,
+        Self::MerkleRootAndMsgHash,
+        Self::Chain,
+        Self::MerkleLeaf,
+        Self::Decomposition,
+        Self::RangeCheck,
+    ];
+
+    pub const fn from_index(index: usize) -> Option<Self> {
+        if index < Self::ALL.len() {
+            Some(Self::ALL[index])
+        } else {
+            None
+        }
+    }
+
+    /// Returns names and lengths of fields of message sent over the bus.
+    pub const fn fields(self) -> &'static [(&'static str, usize)] {
+        match self {
+            Self::Parameter => <ParameterMessage<()> as BusMessage<()>>::FIELDS,
+            Self::MerkleRootAndMsgHash => {
+                <MerkleRootAndMsgHashMessage<()> as BusMessage<()>>::FIELDS
+            }
+            Self::Chain => <ChainMessage<()> as BusMessage<()>>::FIELDS,
+            Self::MerkleLeaf => <MerkleLeafMessage<()> as BusMessage<()>>::FIELDS,
+            Self::Decomposition => <DecompositionMessage<()> as BusMessage<()>>::FIELDS,
+            Self::RangeCheck => <RangeCheckMessage<()> as BusMessage<()>>::FIELDS,
+        }
+    }
+
+    /// Returns amount of field elements of message sent over the bus.
+    pub const fn width(self) -> usize {
+        let fields = self.fields();
+        let mut width = 0;
+        let mut idx = 0;
+        while idx < fields.len() {
+            width += fields[idx].1;
+            idx += 1;
+        }
+        width
+    }
+}
+
+/// Message sent over [`BusMessage::BUS`], which is flattened in order of [`BusMessage::FIELDS`].
+pub trait BusMessage<T> {
+    const BUS: Bus;
+    const FIELDS: &'static [(&'static str, usize)];
+
+    fn into_fields(self) -> impl Iterator<Item = T>;
+}
+
+/// Sends and receives [`BusMessage`], so sender and receiver of the same bus can't disagree on
+/// arity or order of fields.
+pub trait BusInteractionBuilder: InteractionBuilder {
+    #[inline]
+    fn send<T: Into<Self::Expr>, M: BusMessage<T>>(
+        &mut self,
+        msg: M,
+        count: impl Into<Self::Expr>,
+    ) {
+        self.push_send(M::BUS as usize, msg.into_fields(), count);
+    }
+
+    #[inline]
+    fn receive<T: Into<Self::Expr>, M: BusMessage<T>>(
+        &mut self,
+        msg: M,
+        count: impl Into<Self::Expr>,
+    ) {
+        self.push_receive(M::BUS as usize, msg.into_fields(), count);
+    }
+}
+
+impl<AB: InteractionBuilder> BusInteractionBuilder for AB {}
+
+/// Parameter of the signer, sent by `Main` and received by `Chain`.
+#[derive(Clone, Debug)]
+pub struct ParameterMessage<T> {
+    pub sig_idx: T,
+    pub parameter: [T; PARAM_FE_LEN],
+}
+
+impl<T> BusMessage<T> for ParameterMessage<T> {
+    const BUS: Bus = Bus::Parameter;
+    const FIELDS: &'static [(&'static str, usize)] = &[("sig_idx", 1), ("parameter", PARAM_FE_LEN)];
+
+    #[inline]
+    fn into_fields(self) -> impl Iterator<Item = T> {
+        iter::once(self.sig_idx).chain(self.parameter)
+    }
+}
+
+/// Merkle root and message hash of the signer, sent by `Main` and received by `MerkleTree`.
+#[derive(Clone, Debug)]
+pub struct MerkleRootAndMsgHashMessage<T> {
+    pub sig_idx: T,
+    pub parameter: [T; PARAM_FE_LEN],
+    pub merkle_root: [T; HASH_FE_LEN],
+    pub msg_hash: [T; MSG_HASH_FE_LEN],
+}
+
+impl<T> BusMessage<T> for MerkleRootAndMsgHashMessage<T> {
+    const BUS: Bus = Bus::MerkleRootAndMsgHash;
+    const FIELDS: &'static [(&'static str, usize)] = &[
+        ("sig_idx", 1),
+        ("parameter", PARAM_FE_LEN),
+        ("merkle_root", HASH_FE_LEN),
+        ("msg_hash", MSG_HASH_FE_LEN),
+    ];
+
+    #[inline]
+    fn into_fields(self) -> impl Iterator<Item = T> {
+        iter::once(self.sig_idx)
+            .chain(self.parameter)
+            .chain(self.merkle_root)
+            .chain(self.msg_hash)
+    }
+}
+
+/// Start step of the `chain_idx`-th chain, sent by `Decomposition` and received by `Chain`.
+#[derive(Clone, Debug)]
+pub struct ChainMessage<T> {
+    pub sig_idx: T,
+    pub chain_idx: T,
+    pub chain_step: T,
+}
+
+impl<T> BusMessage<T> for ChainMessage<T> {
+    const BUS: Bus = Bus::Chain;
+    const FIELDS: &'static [(&'static str, usize)] =
+        &[("sig_idx", 1), ("chain_idx", 1), ("chain_step", 1)];
+
+    #[inline]
+    fn into_fields(self) -> impl Iterator<Item = T> {
+        [self.sig_idx, self.chain_idx, self.chain_step].into_iter()
+    }
+}
+
+/// The `leaf_chunk_idx`-th chunk of Merkle leaf, which is the end of the `(leaf_chunk_idx - 1)`-th
+/// chain, sent by `Chain` and received by `MerkleTree`.
+#[derive(Clone, Debug)]
+pub struct MerkleLeafMessage<T> {
+    pub sig_idx: T,
+    pub leaf_chunk_idx: T,
+    pub leaf_chunk: [T; HASH_FE_LEN],
+}
+
+impl<T> BusMessage<T> for MerkleLeafMessage<T> {
+    const BUS: Bus = Bus::MerkleLeaf;
+    const FIELDS: &'static [(&'static str, usize)] = &[
+        ("sig_idx", 1),
+        ("leaf_chunk_idx", 1),
+        ("leaf_chunk", HASH_FE_LEN),
+    ];
+
+    #[inline]
+    fn into_fields(self) -> impl Iterator<Item = T> {
+        [self.sig_idx, self.leaf_chunk_idx]
+            .into_iter()
+            .chain(self.leaf_chunk)
+    }
+}
+
+/// Message hash of the signer in reversed order, sent by `Main` and received by `Decomposition`.
+#[derive(Clone, Debug)]
+pub struct DecompositionMessage<T> {
+    pub sig_idx: T,
+    pub msg_hash_rev: [T; MSG_HASH_FE_LEN],
+}
+
+impl<T> BusMessage<T> for DecompositionMessage<T> {
+    const BUS: Bus = Bus::Decomposition;
+    const FIELDS: &'static [(&'static str, usize)] =
+        &[("sig_idx", 1), ("msg_hash_rev", MSG_HASH_FE_LEN)];
+
+    #[inline]
+    fn into_fields(self) -> impl Iterator<Item = T> {
+        iter::once(self.sig_idx).chain(self.msg_hash_rev)
+    }
+}
+
+/// Value to check in range `0..1 << LIMB_BITS`, sent by `Decomposition` and received by
+/// `RangeCheck`.
+#[derive(Clone, Debug)]
+pub struct RangeCheckMessage<T> {
+    pub value: T,
+}
+
+impl<T> BusMessage<T> for RangeCheckMessage<T> {
+    const BUS: Bus = Bus::RangeCheck;
+    const FIELDS: &'static [(&'static str, usize)] = &[("value", 1)];
+
+    #[inline]
+    fn into_fields(self) -> impl Iterator<Item = T> {
+        iter::once(self.value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        air::{Bus, HashSigAggAir},
+        hash_sig::F,
+    };
+    use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
+    use p3_air_ext::InteractionBuilder;
+    use p3_field::PrimeCharacteristicRing;
+    use p3_matrix::dense::RowMajorMatrix;
+    use std::collections::HashMap;
+
+    /// Records width of each message sent or received, with all cells being zero.
+    struct BusRecorder {
+        main: RowMajorMatrix<F>,
+        public_values: Vec<F>,
+        sends: HashMap<usize, Vec<usize>>,
+        receives: HashMap<usize, Vec<usize>>,
+    }
+
+    impl AirBuilder for BusRecorder {
+        type Expr = F;
+        type Var = F;
+        type M = RowMajorMatrix<F>;
+        type F = F;
+
+        fn main(&self) -> Self::M {
+            self.main.clone()
+        }
+
+        fn is_first_row(&self) -> Self::Expr {
+            F::ZERO
+        }
+
+        fn is_last_row(&self) -> Self::Expr {
+            F::ZERO
+        }
+
+        fn is_transition_window(&self, _size: usize) -> Self::Expr {
+            F::ZERO
+        }
+
+        fn assert_zero<I: Into<Self::Expr>>(&mut self, _: I) {}
+    }
+
+    impl AirBuilderWithPublicValues for BusRecorder {
+        type PublicVar = F;
+
+        fn public_values(&self) -> &[Self::PublicVar] {
+            &self.public_values
+        }
+    }
+
+    impl InteractionBuilder for BusRecorder {
+        const ONLY_INTERACTION: bool = true;
+
+        fn push_interaction(
+            &mut self,
+            _: usize,
+            _: impl IntoIterator<Item: Into<Self::Expr>>,
+            _: impl Into<Self::Expr>,
+            _: u32,
+        ) {
+            unreachable!()
+        }
+
+        fn push_send(
+            &mut self,
+            bus_index: usize,
+            fields: impl IntoIterator<Item: Into<Self::Expr>>,
+            _: impl Into<Self::Expr>,
+        ) {
+            let width = fields.into_iter().count();
+            self.sends.entry(bus_index).or_default().push(width);
+        }
+
+        fn push_receive(
+            &mut self,
+            bus_index: usize,
+            fields: impl IntoIterator<Item: Into<Self::Expr>>,
+            _: impl Into<Self::Expr>,
+        ) {
+            let width = fields.into_iter().count();
+            self.receives.entry(bus_index).or_default().push(width);
+        }
+    }
+
+    #[test]
+    fn bus_balance() {
+        let airs: [HashSigAggAir; 5] = [
+            HashSigAggAir::Chain(Default::default()),
+            HashSigAggAir::Decomposition(Default::default()),
+            HashSigAggAir::Main(Default::default()),
+            HashSigAggAir::MerkleTree(Default::default()),
+            HashSigAggAir::RangeCheck(Default::default()),
+        ];
+        let mut recorder = BusRecorder {
+            main: RowMajorMatrix::new(Vec::new(), 1),
+            public_values: Vec::new(),
+            sends: HashMap::new(),
+            receives: HashMap::new(),
+        };
+        for air in airs {
+            recorder.main = RowMajorMatrix::new(vec![F::ZERO; 2 * air.width()], air.width());
+            recorder.public_values = vec![F::ZERO; air.num_public_values()];
+            air.eval(&mut recorder);
+        }
+        for bus in Bus::ALL {
+            let sends = recorder
+                .sends
+                .get(&(bus as usize))
+                .unwrap_or_else(|| panic!("no sender of {bus:?}"));
+            let receives = recorder
+                .receives
+                .get(&(bus as usize))
+                .unwrap_or_else(|| panic!("no receiver of {bus:?}"));
+            assert!(
+                sends
+                    .iter()
+                    .chain(receives)
+                    .all(|width| *width == bus.width()),
+                "unmatched width of {bus:?}"
+            );
+        }
+    }
+}
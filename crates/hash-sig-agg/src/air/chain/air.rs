@@ -1,6 +1,6 @@
 use crate::{
     air::{
-        Bus,
+        BusInteractionBuilder, ChainMessage, MerkleLeafMessage, ParameterMessage,
         chain::{
             column::{ChainCols, NUM_CHAIN_COLS},
             poseidon2::{PARTIAL_ROUNDS, WIDTH},
@@ -10,7 +10,7 @@ use crate::{
     hash_sig::{F, HALF_FULL_ROUNDS, Poseidon2LinearLayers, RC16, SBOX_DEGREE, SBOX_REGISTERS},
     util::zip,
 };
-use core::{borrow::Borrow, iter};
+use core::borrow::Borrow;
 use itertools::Itertools;
 use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
 use p3_air_ext::{InteractionBuilder, SubAirBuilder};
@@ -233,9 +233,11 @@ fn receive_parameter<AB>(builder: &mut AB, local: &ChainCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_receive(
-        Bus::Parameter as usize,
-        iter::once(local.sig_idx).chain(local.parameter()),
+    builder.receive(
+        ParameterMessage {
+            sig_idx: local.sig_idx,
+            parameter: local.parameter(),
+        },
         (*local.is_active).into() * local.is_last_sig_row::<AB>(),
     );
 }
@@ -245,13 +247,12 @@ fn receive_chain<AB>(builder: &mut AB, local: &ChainCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_receive(
-        Bus::Chain as usize,
-        [
-            local.sig_idx.into(),
-            (*local.chain_idx).into(),
-            local.chain_step::<AB>(),
-        ],
+    builder.receive(
+        ChainMessage {
+            sig_idx: local.sig_idx.into(),
+            chain_idx: (*local.chain_idx).into(),
+            chain_step: local.chain_step::<AB>(),
+        },
         (*local.is_active).into() * local.is_x_i.into(),
     );
 }
@@ -261,14 +262,12 @@ fn send_merkle_tree<AB>(builder: &mut AB, local: &ChainCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_send(
-        Bus::MerkleLeaf as usize,
-        [
-            local.sig_idx.into(),
-            (*local.chain_idx).into() + AB::Expr::ONE,
-        ]
-        .into_iter()
-        .chain(local.compression_output::<AB>()),
+    builder.send(
+        MerkleLeafMessage {
+            sig_idx: local.sig_idx.into(),
+            leaf_chunk_idx: (*local.chain_idx).into() + AB::Expr::ONE,
+            leaf_chunk: local.compression_output::<AB>(),
+        },
         *local.is_active * local.is_last_chain_step::<AB>(),
     );
 }
@@ -44,6 +44,16 @@ pub struct ChainCols<T> {
     pub is_x_i: T,
 }
 
+crate::util::column::impl_column_names!(ChainCols {
+    perm,
+    is_active,
+    sig_idx,
+    sig_step,
+    chain_idx,
+    chain_step_bits,
+    is_x_i,
+});
+
 impl<T> ChainCols<T> {
     #[inline]
     pub const fn as_slice(&self) -> &[T] {
@@ -9,52 +9,58 @@ use crate::{
     },
     util::{
         field::{MaybeUninitField, MaybeUninitFieldSlice},
-        par_zip, zip,
+        par_zip,
+        trace::UninitTrace,
+        zip,
     },
 };
 use core::mem::MaybeUninit;
 use itertools::Itertools;
 use p3_field::PrimeCharacteristicRing;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 use p3_poseidon2_util::air::generate_trace_rows_for_perm;
 
 const MAX_X_I: u32 = (1 << CHUNK_SIZE) - 1;
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    (traces.len() * TARGET_SUM as usize).next_power_of_two()
+pub const NUM_ROWS_PER_SIG: usize = TARGET_SUM as usize;
+
+pub const fn trace_height(num_sigs: usize) -> usize {
+    (num_sigs * NUM_ROWS_PER_SIG).next_power_of_two()
 }
 
 pub fn generate_trace(
     extra_capacity_bits: usize,
     traces: &[VerificationTrace],
 ) -> RowMajorMatrix<F> {
-    let height = trace_height(traces);
-    let size = height * NUM_CHAIN_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_CHAIN_COLS);
-
-    let (prefix, rows, suffix) =
-        unsafe { trace.values.align_to_mut::<ChainCols<MaybeUninit<F>>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
+    let mut trace = UninitTrace::new(
+        extra_capacity_bits,
+        trace_height(traces.len()),
+        NUM_CHAIN_COLS,
+    );
 
-    let (rows, padding_rows) = rows.split_at_mut(traces.len() * TARGET_SUM as usize);
+    // SAFETY: `ChainCols` is `#[repr(C)]` of `MaybeUninit<F>` with the trace width.
+    let (rows, padding_rows) =
+        unsafe { trace.rows_mut::<ChainCols<_>>() }.split_at_mut(traces.len() * NUM_ROWS_PER_SIG);
 
     join(
-        || {
-            par_zip!(rows.par_chunks_mut(TARGET_SUM as _), traces)
-                .enumerate()
-                .for_each(|(sig_idx, (rows, trace))| generate_sig_rows(rows, sig_idx, trace));
-        },
+        || generate_sigs_rows(rows, 0, traces),
         || generate_padding_rows(padding_rows),
     );
 
-    unsafe { vec.set_len(size) };
+    // SAFETY: All rows are written by `generate_sigs_rows` and `generate_padding_rows`.
+    unsafe { trace.assume_init() }
+}
 
-    RowMajorMatrix::new(vec, NUM_CHAIN_COLS)
+/// Generates rows of `traces` with signature index starting from `sig_idx_offset`.
+pub fn generate_sigs_rows(
+    rows: &mut [ChainCols<MaybeUninit<F>>],
+    sig_idx_offset: usize,
+    traces: &[VerificationTrace],
+) {
+    par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
+        .enumerate()
+        .for_each(|(idx, (rows, trace))| generate_sig_rows(rows, sig_idx_offset + idx, trace));
 }
 
 #[inline]
@@ -87,21 +93,28 @@ fn generate_sig_rows(
         .tuple_windows()
         .for_each(|(i, i_next)| {
             let x_i = u32::from(trace.x[i as usize]);
-            zip!(x_i..MAX_X_I, rows.by_ref().take((MAX_X_I - x_i) as usize)).for_each(
-                |(chain_step, row)| {
+            // Rows run out before steps when chunks of the message hash take more than
+            // `TARGET_SUM` steps in total, so zip without checking the lengths.
+            (x_i..MAX_X_I)
+                .zip(rows.by_ref())
+                .for_each(|(chain_step, row)| {
                     row.chain_idx.populate(i, i_next);
                     row.chain_step_bits.fill_from_iter(
                         (0..CHUNK_SIZE).map(|idx| F::from_bool((chain_step >> idx) & 1 == 1)),
                     );
                     row.is_x_i.write_bool(chain_step == x_i);
-                },
-            );
+                });
         });
-    debug_assert!(rows.next().is_none());
+    // Only left when chunks of the message hash don't sum up to `TARGET_SUM`.
+    rows.for_each(|row| {
+        row.chain_idx.populate_padding();
+        row.chain_step_bits.fill_zero();
+        row.is_x_i.write_zero();
+    });
 }
 
 #[inline]
-fn generate_padding_rows(rows: &mut [ChainCols<MaybeUninit<F>>]) {
+pub fn generate_padding_rows(rows: &mut [ChainCols<MaybeUninit<F>>]) {
     if let Some((template, rows)) = rows.split_first_mut() {
         generate_padding_row(template);
         let template = template.as_slice();
@@ -1,14 +1,14 @@
 use crate::{
     air::{HashSigAggAir, HashSigAggInteraction, chain::generation::generate_trace},
     hash_sig::{F, NUM_CHUNKS, VerificationTrace},
-    util::air_instance::AirInstance,
+    util::air_instance::{AirDescriptor, AirInstance},
 };
 use p3_field::PrimeCharacteristicRing;
 use p3_matrix::dense::RowMajorMatrix;
 
 mod air;
 mod column;
-mod generation;
+pub(super) mod generation;
 
 mod poseidon2 {
     pub const WIDTH: usize = 16;
@@ -35,6 +35,13 @@ impl AirInstance<F> for ChainAirInstance<'_> {
     type Air = HashSigAggAir;
     type Interaction = HashSigAggInteraction;
 
+    fn descriptor(&self) -> AirDescriptor {
+        AirDescriptor {
+            name: "Chain",
+            consumes_interaction: false,
+        }
+    }
+
     fn air(&self) -> HashSigAggAir {
         HashSigAggAir::Chain(Default::default())
     }
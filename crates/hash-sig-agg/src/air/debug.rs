@@ -0,0 +1,472 @@
+use crate::{
+    air::{Bus, ExtensionAir, HashSigAggAir},
+    hash_sig::F,
+};
+use core::fmt::{self, Display};
+use itertools::Itertools;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_air_ext::{InteractionBuilder, ProverInput};
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::{Matrix, dense::RowMajorMatrix};
+use p3_maybe_rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Where an interaction is pushed and with which signed count (positive for send).
+#[derive(Clone, Debug)]
+pub struct InteractionSource {
+    pub air: &'static str,
+    pub row: usize,
+    pub count: F,
+}
+
+/// Interaction whose counts over all AIRs don't sum up to zero.
+#[derive(Clone, Debug)]
+pub struct UnbalancedInteraction {
+    pub bus_index: usize,
+    pub fields: Vec<F>,
+    pub count: F,
+    pub sources: Vec<InteractionSource>,
+}
+
+impl Display for UnbalancedInteraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match Bus::from_index(self.bus_index) {
+            Some(bus) if bus.width() == self.fields.len() => {
+                write!(f, "{bus:?} {{ ")?;
+                let mut fields = self.fields.iter();
+                for (idx, (name, len)) in bus.fields().iter().enumerate() {
+                    let values = fields.by_ref().take(*len);
+                    let sep = if idx == 0 { "" } else { ", " };
+                    if *len == 1 {
+                        write!(f, "{sep}{name}: {}", values.format(""))?;
+                    } else {
+                        write!(f, "{sep}{name}: [{}]", values.format(", "))?;
+                    }
+                }
+                write!(f, " }}")?;
+            }
+            _ => write!(
+                f,
+                "bus {} [{}]",
+                self.bus_index,
+                self.fields.iter().format(", ")
+            )?,
+        }
+        write!(f, " unbalanced by {}, pushed by", self.count)?;
+        for source in &self.sources {
+            write!(
+                f,
+                " {}[{}] (count {})",
+                source.air, source.row, source.count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates interactions of every row of every AIR, and returns the ones that don't balance out
+/// on their bus, ordered by bus index.
+pub fn unbalanced_interactions<T: ExtensionAir>(
+    prover_inputs: &[ProverInput<F, HashSigAggAir<T>>],
+) -> Vec<UnbalancedInteraction>
+where
+    HashSigAggAir<T>: for<'a> Air<RowWindowBuilder<'a>>,
+{
+    let mut interactions = HashMap::<_, (F, Vec<_>)>::new();
+    for prover_input in prover_inputs {
+        let air = prover_input.air();
+        let trace = prover_input.trace();
+        let height = trace.height();
+        for row in 0..height {
+            let mut builder = RowWindowBuilder::new(trace, prover_input.public_values(), row);
+            air.eval(&mut builder);
+            for (bus_index, fields, count) in builder.interactions {
+                let (sum, sources) = interactions.entry((bus_index, fields)).or_default();
+                *sum += count;
+                sources.push(InteractionSource {
+                    air: air.name(),
+                    row,
+                    count,
+                });
+            }
+        }
+    }
+    interactions
+        .into_iter()
+        .filter(|(_, (count, _))| *count != F::ZERO)
+        .map(
+            |((bus_index, fields), (count, sources))| UnbalancedInteraction {
+                bus_index,
+                fields,
+                count,
+                sources,
+            },
+        )
+        .sorted_by_key(|interaction| interaction.bus_index)
+        .collect()
+}
+
+/// Returns the window of `row` and its next row, wrapping around at the last row.
+fn window(trace: &RowMajorMatrix<F>, row: usize) -> RowMajorMatrix<F> {
+    let width = trace.width();
+    let next = (row + 1) % trace.height();
+    RowMajorMatrix::new(
+        trace.values[row * width..(row + 1) * width]
+            .iter()
+            .chain(&trace.values[next * width..(next + 1) * width])
+            .copied()
+            .collect(),
+        width,
+    )
+}
+
+/// Evaluates interactions on the window of `row` and its next row, with constraints skipped.
+pub struct RowWindowBuilder<'a> {
+    main: RowMajorMatrix<F>,
+    public_values: &'a [F],
+    row: usize,
+    height: usize,
+    interactions: Vec<(usize, Vec<F>, F)>,
+}
+
+impl<'a> RowWindowBuilder<'a> {
+    fn new(trace: &RowMajorMatrix<F>, public_values: &'a [F], row: usize) -> Self {
+        Self {
+            main: window(trace, row),
+            public_values,
+            row,
+            height: trace.height(),
+            interactions: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, bus_index: usize, fields: impl IntoIterator<Item: Into<F>>, count: F) {
+        if count != F::ZERO {
+            let fields = fields.into_iter().map(Into::into).collect();
+            self.interactions.push((bus_index, fields, count));
+        }
+    }
+}
+
+impl AirBuilder for RowWindowBuilder<'_> {
+    type Expr = F;
+    type Var = F;
+    type M = RowMajorMatrix<F>;
+    type F = F;
+
+    fn main(&self) -> Self::M {
+        self.main.clone()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        F::from_bool(self.row == 0)
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        F::from_bool(self.row == self.height - 1)
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        assert_eq!(size, 2, "only window size 2 is supported");
+        F::from_bool(self.row != self.height - 1)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, _: I) {}
+}
+
+impl AirBuilderWithPublicValues for RowWindowBuilder<'_> {
+    type PublicVar = F;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.public_values
+    }
+}
+
+impl InteractionBuilder for RowWindowBuilder<'_> {
+    const ONLY_INTERACTION: bool = true;
+
+    fn push_interaction(
+        &mut self,
+        bus_index: usize,
+        fields: impl IntoIterator<Item: Into<Self::Expr>>,
+        count: impl Into<Self::Expr>,
+        _: u32,
+    ) {
+        self.push(bus_index, fields, count.into());
+    }
+
+    fn push_send(
+        &mut self,
+        bus_index: usize,
+        fields: impl IntoIterator<Item: Into<Self::Expr>>,
+        count: impl Into<Self::Expr>,
+    ) {
+        self.push(bus_index, fields, count.into());
+    }
+
+    fn push_receive(
+        &mut self,
+        bus_index: usize,
+        fields: impl IntoIterator<Item: Into<Self::Expr>>,
+        count: impl Into<Self::Expr>,
+    ) {
+        self.push(bus_index, fields, -count.into());
+    }
+}
+
+/// Column involved in a failed constraint, prefixed by `next.` if it's in the next row.
+#[derive(Clone, Debug)]
+pub struct InvolvedColumn {
+    pub name: String,
+    pub value: F,
+}
+
+/// Constraint that doesn't evaluate to zero, where `constraint` is the index of the
+/// `assert_zero` call in order of evaluation.
+#[derive(Clone, Debug)]
+pub struct FailedConstraint {
+    pub air: &'static str,
+    pub row: usize,
+    pub constraint: usize,
+    pub value: F,
+    pub columns: Vec<InvolvedColumn>,
+}
+
+impl Display for FailedConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}Air row {}: constraint {} evaluates to {}",
+            self.air, self.row, self.constraint, self.value
+        )?;
+        for (idx, column) in self.columns.iter().enumerate() {
+            let sep = if idx == 0 { " with " } else { ", " };
+            write!(f, "{sep}`{}` = {}", column.name, column.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates constraints of every row of every AIR, and returns the failed ones ordered by AIR
+/// and row.
+///
+/// Columns involved in a failed constraint are found by perturbing each cell of the window and
+/// checking if the constraint changes, so a column is missed if the perturbation happens to
+/// leave the constraint unchanged.
+pub fn failed_constraints<T: BaseAir<F> + ExtensionAir + Sync>(
+    prover_inputs: &[ProverInput<F, HashSigAggAir<T>>],
+) -> Vec<FailedConstraint>
+where
+    HashSigAggAir<T>: for<'a> Air<ConstraintWindowBuilder<'a>>,
+{
+    prover_inputs
+        .iter()
+        .flat_map(|prover_input| {
+            let air = prover_input.air();
+            let trace = prover_input.trace();
+            let names = air.column_names();
+            (0..trace.height())
+                .into_par_iter()
+                .map(|row| failed_constraints_of_row(prover_input, &names, row))
+                .collect::<Vec<_>>()
+        })
+        .flatten()
+        .collect()
+}
+
+fn failed_constraints_of_row<T: ExtensionAir>(
+    prover_input: &ProverInput<F, HashSigAggAir<T>>,
+    names: &[String],
+    row: usize,
+) -> Vec<FailedConstraint>
+where
+    HashSigAggAir<T>: for<'a> Air<ConstraintWindowBuilder<'a>>,
+{
+    let air = prover_input.air();
+    let trace = prover_input.trace();
+    let eval = |main| {
+        let mut builder = ConstraintWindowBuilder {
+            main,
+            public_values: prover_input.public_values(),
+            row,
+            height: trace.height(),
+            constraints: Vec::new(),
+        };
+        air.eval(&mut builder);
+        builder.constraints
+    };
+
+    let main = window(trace, row);
+    let constraints = eval(main.clone());
+    let failed = constraints
+        .iter()
+        .positions(|value| *value != F::ZERO)
+        .collect::<Vec<_>>();
+    if failed.is_empty() {
+        return Vec::new();
+    }
+
+    let mut involved = vec![Vec::new(); failed.len()];
+    for col in 0..main.values.len() {
+        let mut perturbed = main.clone();
+        perturbed.values[col] += F::ONE;
+        let perturbed = eval(perturbed);
+        for (involved, constraint) in involved.iter_mut().zip(&failed) {
+            if perturbed[*constraint] != constraints[*constraint] {
+                involved.push(InvolvedColumn {
+                    name: names
+                        .get(col)
+                        .cloned()
+                        .unwrap_or_else(|| format!("next.{}", names[col - main.width()])),
+                    value: main.values[col],
+                });
+            }
+        }
+    }
+
+    failed
+        .into_iter()
+        .zip(involved)
+        .map(|(constraint, columns)| FailedConstraint {
+            air: air.name(),
+            row,
+            constraint,
+            value: constraints[constraint],
+            columns,
+        })
+        .collect()
+}
+
+/// Evaluates constraints on the window of `row` and its next row, with interactions skipped.
+pub struct ConstraintWindowBuilder<'a> {
+    main: RowMajorMatrix<F>,
+    public_values: &'a [F],
+    row: usize,
+    height: usize,
+    constraints: Vec<F>,
+}
+
+impl AirBuilder for ConstraintWindowBuilder<'_> {
+    type Expr = F;
+    type Var = F;
+    type M = RowMajorMatrix<F>;
+    type F = F;
+
+    fn main(&self) -> Self::M {
+        self.main.clone()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        F::from_bool(self.row == 0)
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        F::from_bool(self.row == self.height - 1)
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        assert_eq!(size, 2, "only window size 2 is supported");
+        F::from_bool(self.row != self.height - 1)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.constraints.push(x.into());
+    }
+}
+
+impl AirBuilderWithPublicValues for ConstraintWindowBuilder<'_> {
+    type PublicVar = F;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.public_values
+    }
+}
+
+impl InteractionBuilder for ConstraintWindowBuilder<'_> {
+    const ONLY_INTERACTION: bool = false;
+
+    fn push_interaction(
+        &mut self,
+        _: usize,
+        _: impl IntoIterator<Item: Into<Self::Expr>>,
+        _: impl Into<Self::Expr>,
+        _: u32,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        air::{
+            Bus,
+            chain::{ChainCols, NUM_CHAIN_COLS},
+            debug::{failed_constraints, unbalanced_interactions},
+            generate_prover_inputs,
+            main::{MainCols, NUM_MAIN_COLS},
+        },
+        hash_sig::F,
+    };
+    use core::borrow::BorrowMut;
+    use hash_sig_testdata::mock_vi;
+    use p3_air_ext::ProverInput;
+    use p3_field::PrimeCharacteristicRing;
+
+    #[test]
+    fn unbalanced() {
+        let mut prover_inputs = generate_prover_inputs(0, mock_vi(4));
+        assert!(unbalanced_interactions(&prover_inputs).is_empty());
+
+        let main = prover_inputs
+            .iter_mut()
+            .find(|prover_input| prover_input.air().name() == "Main")
+            .unwrap();
+        let mut trace = main.trace().clone();
+        let row: &mut MainCols<F> = trace.values[..NUM_MAIN_COLS].borrow_mut();
+        row.msg_hash[0] += F::ONE;
+        *main = ProverInput::new(main.air().clone(), main.public_values().to_vec(), trace);
+
+        let unbalanced = unbalanced_interactions(&prover_inputs);
+        assert!(!unbalanced.is_empty());
+        assert!(unbalanced.iter().all(|interaction| {
+            [
+                Bus::MerkleRootAndMsgHash as usize,
+                Bus::Decomposition as usize,
+            ]
+            .contains(&interaction.bus_index)
+        }));
+        assert!(unbalanced.iter().any(|interaction| {
+            interaction
+                .sources
+                .iter()
+                .any(|source| source.air == "Main" && source.row == 0)
+        }));
+    }
+
+    #[test]
+    fn failed() {
+        let mut prover_inputs = generate_prover_inputs(0, mock_vi(4));
+        assert!(failed_constraints(&prover_inputs).is_empty());
+
+        let chain = prover_inputs
+            .iter_mut()
+            .find(|prover_input| prover_input.air().name() == "Chain")
+            .unwrap();
+        let mut trace = chain.trace().clone();
+        let row: &mut ChainCols<F> = trace.values[..NUM_CHAIN_COLS].borrow_mut();
+        row.chain_idx.diff_bits[0] = F::TWO;
+        *chain = ProverInput::new(chain.air().clone(), chain.public_values().to_vec(), trace);
+
+        let failed = failed_constraints(&prover_inputs);
+        assert!(!failed.is_empty());
+        assert!(failed.iter().all(|constraint| constraint.air == "Chain"));
+        assert!(failed.iter().any(|constraint| {
+            constraint.row == 0
+                && constraint
+                    .columns
+                    .iter()
+                    .any(|column| column.name == "chain_idx.diff_bits[0]")
+        }));
+        assert!(failed[0].to_string().starts_with("ChainAir row 0: "));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     air::{
-        Bus,
+        BusInteractionBuilder, ChainMessage, DecompositionMessage, RangeCheckMessage,
         decomposition::{
             F_MS_LIMB, F_MS_LIMB_LEADING_ONES, F_MS_LIMB_TRAILING_ZEROS, LIMB_BITS, NUM_LIMBS,
             NUM_MSG_HASH_LIMBS,
@@ -11,7 +11,7 @@ use crate::{
     hash_sig::{F, MSG_HASH_FE_LEN, TARGET_SUM},
     util::zip,
 };
-use core::{borrow::Borrow, iter};
+use core::borrow::Borrow;
 use hash_sig_verifier::instantiation::poseidon2::CHUNK_SIZE;
 use itertools::Itertools;
 use p3_air::{Air, AirBuilder, BaseAir, BaseAirWithPublicValues};
@@ -327,13 +327,12 @@ where
     .enumerate()
     .for_each(|(chunk_idx, (chunk, is_send_chain))| {
         let chunk = chunk.iter().rev().copied().map_into();
-        builder.push_send(
-            Bus::Chain as usize,
-            [
-                cols.sig_idx.into(),
-                i_offset.clone() + F::from_usize(chunk_idx),
-                chunk.reduce(|acc, bit| acc.double() + bit).unwrap(),
-            ],
+        builder.send(
+            ChainMessage {
+                sig_idx: cols.sig_idx.into(),
+                chain_idx: i_offset.clone() + F::from_usize(chunk_idx),
+                chain_step: chunk.reduce(|acc, bit| acc.double() + bit).unwrap(),
+            },
             is_send_chain,
         );
     });
@@ -350,7 +349,7 @@ where
         .chain(&cols.acc_limbs)
         .chain(&cols.carries)
     {
-        builder.push_send(Bus::RangeCheck as usize, [*limb], cols.is_acc::<AB>());
+        builder.send(RangeCheckMessage { value: *limb }, cols.is_acc::<AB>());
     }
 }
 
@@ -359,9 +358,11 @@ fn receive_decomposition<AB>(builder: &mut AB, cols: &DecompositionCols<AB::Var>
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_receive(
-        Bus::Decomposition as usize,
-        iter::once(cols.sig_idx).chain(cols.values),
+    builder.receive(
+        DecompositionMessage {
+            sig_idx: cols.sig_idx,
+            msg_hash_rev: cols.values,
+        },
         cols.is_acc_last_row::<AB>(),
     );
 }
@@ -45,6 +45,22 @@ pub struct DecompositionCols<T> {
     pub sum: T,
 }
 
+crate::util::column::impl_column_names!(DecompositionCols {
+    sig_idx,
+    inds,
+    values,
+    value_ls_limbs,
+    value_ms_limb_bits,
+    value_limb_0_is_zero,
+    value_limb_1_is_zero,
+    is_ms_limb_max,
+    acc_limbs,
+    carries,
+    decomposition_bits,
+    is_send_chain,
+    sum,
+});
+
 impl<T> DecompositionCols<T> {
     #[inline]
     pub const fn as_slice(&self) -> &[T] {
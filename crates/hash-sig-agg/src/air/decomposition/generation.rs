@@ -11,20 +11,21 @@ use crate::{
     util::{
         field::{MaybeUninitField, MaybeUninitFieldSlice},
         par_zip,
+        trace::UninitTrace,
     },
 };
 use core::{array::from_fn, mem::MaybeUninit};
 use itertools::Itertools;
 use p3_field::{PrimeCharacteristicRing, PrimeField32};
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 
 const MAX_X_I: u32 = (1 << CHUNK_SIZE) - 1;
 
-const NUM_ROWS_PER_SIG: usize = MSG_HASH_FE_LEN + NUM_MSG_HASH_LIMBS;
+pub const NUM_ROWS_PER_SIG: usize = MSG_HASH_FE_LEN + NUM_MSG_HASH_LIMBS;
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    (NUM_ROWS_PER_SIG * traces.len()).next_power_of_two()
+pub const fn trace_height(num_sigs: usize) -> usize {
+    (NUM_ROWS_PER_SIG * num_sigs).next_power_of_two()
 }
 
 pub fn generate_trace(
@@ -32,62 +33,56 @@ pub fn generate_trace(
     traces: &[VerificationTrace],
     range_check_mult: &RangeCheckInteraction,
 ) -> RowMajorMatrix<F> {
-    let height = trace_height(traces);
-    let size = height * NUM_DECOMPOSITION_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_DECOMPOSITION_COLS);
-
-    let (prefix, rows, suffix) = unsafe {
-        trace
-            .values
-            .align_to_mut::<DecompositionCols<MaybeUninit<_>>>()
-    };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
+    let mut trace = UninitTrace::new(
+        extra_capacity_bits,
+        trace_height(traces.len()),
+        NUM_DECOMPOSITION_COLS,
+    );
 
-    let (msg_hash_rows, padding_rows) = rows.split_at_mut(NUM_ROWS_PER_SIG * traces.len());
+    // SAFETY: `DecompositionCols` is `#[repr(C)]` of `MaybeUninit<F>` with the trace width.
+    let (msg_hash_rows, padding_rows) = unsafe { trace.rows_mut::<DecompositionCols<_>>() }
+        .split_at_mut(NUM_ROWS_PER_SIG * traces.len());
 
     join(
-        || {
-            par_zip!(msg_hash_rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
-                .enumerate()
-                .for_each(|(sig_idx, (rows, trace))| {
-                    let values = from_fn(|i| trace.msg_hash[MSG_HASH_FE_LEN - 1 - i]); // FIXME: Use little-endian when #9 is resolved.
-                    let mut acc_limbs = Default::default();
-                    let (acc_rows, decomposition_rows) = rows.split_at_mut(MSG_HASH_FE_LEN);
-                    acc_rows.iter_mut().enumerate().for_each(|(step, row)| {
-                        generate_acc_row(
-                            row,
-                            sig_idx,
-                            &mut acc_limbs,
-                            values,
-                            step,
-                            range_check_mult,
-                        );
-                    });
-                    let sums = trace
-                        .x
-                        .chunks(LIMB_BITS / CHUNK_SIZE)
-                        .scan(0u32, |sum, x| {
-                            *sum += u32::from(x.iter().copied().sum::<u16>());
-                            Some(*sum)
-                        })
-                        .collect_vec();
-                    par_zip!(decomposition_rows, sums).enumerate().for_each(
-                        |(step, (row, sum))| {
-                            generate_decomposition_row(row, sig_idx, &acc_limbs, sum, step);
-                        },
-                    );
-                });
-        },
+        || generate_sigs_rows(msg_hash_rows, 0, traces, range_check_mult),
         || generate_padding_rows(padding_rows),
     );
 
-    unsafe { vec.set_len(size) };
+    // SAFETY: All rows are written by `generate_sigs_rows` and `generate_padding_rows`.
+    unsafe { trace.assume_init() }
+}
 
-    RowMajorMatrix::new(vec, NUM_DECOMPOSITION_COLS)
+/// Generates rows of `traces` with signature index starting from `sig_idx_offset`.
+pub fn generate_sigs_rows(
+    rows: &mut [DecompositionCols<MaybeUninit<F>>],
+    sig_idx_offset: usize,
+    traces: &[VerificationTrace],
+    range_check_mult: &RangeCheckInteraction,
+) {
+    par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
+        .enumerate()
+        .for_each(|(idx, (rows, trace))| {
+            let sig_idx = sig_idx_offset + idx;
+            let values = from_fn(|i| trace.msg_hash[MSG_HASH_FE_LEN - 1 - i]); // FIXME: Use little-endian when #9 is resolved.
+            let mut acc_limbs = Default::default();
+            let (acc_rows, decomposition_rows) = rows.split_at_mut(MSG_HASH_FE_LEN);
+            acc_rows.iter_mut().enumerate().for_each(|(step, row)| {
+                generate_acc_row(row, sig_idx, &mut acc_limbs, values, step, range_check_mult);
+            });
+            let sums = trace
+                .x
+                .chunks(LIMB_BITS / CHUNK_SIZE)
+                .scan(0u32, |sum, x| {
+                    *sum += u32::from(x.iter().copied().sum::<u16>());
+                    Some(*sum)
+                })
+                .collect_vec();
+            par_zip!(decomposition_rows, sums)
+                .enumerate()
+                .for_each(|(step, (row, sum))| {
+                    generate_decomposition_row(row, sig_idx, &acc_limbs, sum, step);
+                });
+        });
 }
 
 #[inline]
@@ -180,7 +175,7 @@ fn generate_decomposition_row(
 }
 
 #[inline]
-fn generate_padding_rows(rows: &mut [DecompositionCols<MaybeUninit<F>>]) {
+pub fn generate_padding_rows(rows: &mut [DecompositionCols<MaybeUninit<F>>]) {
     if let Some((template, rows)) = rows.split_first_mut() {
         generate_padding_row(template);
         let template = template.as_slice();
@@ -1,14 +1,14 @@
 use crate::{
     air::{HashSigAggAir, HashSigAggInteraction, decomposition::generation::generate_trace},
     hash_sig::{F, MSG_HASH_FE_LEN, VerificationTrace},
-    util::air_instance::AirInstance,
+    util::air_instance::{AirDescriptor, AirInstance},
 };
 use p3_field::PrimeField32;
 use p3_matrix::dense::RowMajorMatrix;
 
 mod air;
 mod column;
-mod generation;
+pub(super) mod generation;
 
 pub const LIMB_BITS: usize = 12;
 pub const LIMB_MASK: u32 = (1 << LIMB_BITS) - 1;
@@ -45,6 +45,13 @@ impl AirInstance<F> for DecompositionAirInstance<'_> {
     type Air = HashSigAggAir;
     type Interaction = HashSigAggInteraction;
 
+    fn descriptor(&self) -> AirDescriptor {
+        AirDescriptor {
+            name: "Decomposition",
+            consumes_interaction: false,
+        }
+    }
+
     fn air(&self) -> HashSigAggAir {
         HashSigAggAir::Decomposition(Default::default())
     }
@@ -0,0 +1,102 @@
+use crate::{
+    air::{ExtensionAir, HashSigAggAir, chain, decomposition, main, merkle_tree},
+    hash_sig::F,
+};
+use core::ops::Range;
+use itertools::Itertools;
+use p3_air::BaseAir;
+use p3_air_ext::ProverInput;
+use p3_matrix::Matrix;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// Returns rows of signatures in `sigs`, or `None` if rows of `air` don't belong to signatures.
+const fn sig_rows<T>(air: &HashSigAggAir<T>, sigs: &Range<usize>) -> Option<Range<usize>> {
+    let num_rows_per_sig = match air {
+        HashSigAggAir::Chain(_) => chain::generation::NUM_ROWS_PER_SIG,
+        HashSigAggAir::Decomposition(_) => decomposition::generation::NUM_ROWS_PER_SIG,
+        HashSigAggAir::Main(_) => main::generation::NUM_ROWS_PER_SIG,
+        HashSigAggAir::MerkleTree(_) => merkle_tree::generation::NUM_ROWS_PER_SIG,
+        HashSigAggAir::RangeCheck(_) | HashSigAggAir::Extension(_) => return None,
+    };
+    Some(sigs.start * num_rows_per_sig..sigs.end * num_rows_per_sig)
+}
+
+/// Writes trace of `prover_input` as CSV with header of column names, where the first column is
+/// the row index.
+///
+/// When `sigs` is given, only rows of signatures in the range are written, except for AIRs whose
+/// rows don't belong to signatures, e.g. `RangeCheck`, which are written in full.
+pub fn write_trace_csv<T: BaseAir<F>>(
+    mut writer: impl Write,
+    prover_input: &ProverInput<F, HashSigAggAir<T>>,
+    sigs: Option<&Range<usize>>,
+) -> io::Result<()> {
+    let air = prover_input.air();
+    let trace = prover_input.trace();
+    let height = trace.height();
+    let rows = sigs
+        .and_then(|sigs| sig_rows(air, sigs))
+        .map_or(0..height, |rows| {
+            rows.start.min(height)..rows.end.min(height)
+        });
+
+    writeln!(writer, "row,{}", air.column_names().iter().format(","))?;
+    for row in rows {
+        let values = &trace.values[row * trace.width()..(row + 1) * trace.width()];
+        writeln!(writer, "{row},{}", values.iter().format(","))?;
+    }
+    writer.flush()
+}
+
+/// Writes trace of each AIR into `{dir}/{name}.csv` by [`write_trace_csv`], where `dir` is
+/// created if missing.
+pub fn export_traces_csv<T: BaseAir<F> + ExtensionAir>(
+    dir: impl AsRef<Path>,
+    prover_inputs: &[ProverInput<F, HashSigAggAir<T>>],
+    sigs: Option<&Range<usize>>,
+) -> io::Result<()> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    prover_inputs.iter().try_for_each(|prover_input| {
+        let file = File::create(dir.join(format!("{}.csv", prover_input.air().name())))?;
+        write_trace_csv(BufWriter::new(file), prover_input, sigs)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::air::{export::write_trace_csv, generate_prover_inputs};
+    use hash_sig_testdata::mock_vi;
+    use itertools::Itertools;
+
+    #[test]
+    fn csv() {
+        let prover_inputs = generate_prover_inputs(0, mock_vi(4));
+        let main = prover_inputs
+            .iter()
+            .find(|prover_input| prover_input.air().name() == "Main")
+            .unwrap();
+
+        let mut csv = Vec::new();
+        write_trace_csv(&mut csv, main, Some(&(1..3))).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        let header = lines[0].split(',').collect::<Vec<_>>();
+        let is_active = header
+            .iter()
+            .position(|name| *name == "is_active.is_active")
+            .unwrap();
+        assert_eq!(header[0], "row");
+        assert_eq!(header[is_active + 1..][..2], ["sig_idx", "parameter[0]"]);
+        let cells = |line: &str| line.split(',').skip(is_active).take(2).join(",");
+        assert!(lines[1].starts_with("1,"));
+        assert_eq!(cells(lines[1]), "1,1");
+        assert!(lines[2].starts_with("2,"));
+        assert_eq!(cells(lines[2]), "1,2");
+    }
+}
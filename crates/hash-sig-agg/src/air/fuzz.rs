@@ -0,0 +1,128 @@
+//! Differential test between native verification and the AIRs on randomly corrupted pairs.
+//!
+//! Runs `FUZZ_ITERATIONS` (default 16) iterations from `FUZZ_SEED` (default 0), which seeds both
+//! the pairs and the corruptions.
+
+use crate::{
+    air::{
+        debug::{failed_constraints, unbalanced_interactions},
+        generate_prover_inputs,
+    },
+    hash_sig::{
+        CHUNK_SIZE, F, HASH_FE_LEN, LOG_LIFETIME, MODULUS, NUM_CHUNKS, PARAM_FE_LEN,
+        Poseidon2TargetSum, PublicKey, RHO_FE_LEN, Signature, TARGET_SUM, VerificationInput,
+        VerificationTrace, encode_msg,
+    },
+};
+use core::array::from_fn;
+use hash_sig_testdata::mock_vi_with_seed;
+use hash_sig_verifier::instantiation::Instantiation;
+use p3_field::PrimeCharacteristicRing;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::env;
+
+const NUM_SIGS: usize = 2;
+
+/// Single field element of a pair to corrupt.
+#[derive(Clone, Copy, Debug)]
+enum Corruption {
+    Rho(usize),
+    OneTimeSig(usize, usize),
+    MerkleSibling(usize, usize),
+    Parameter(usize),
+    MerkleRoot(usize),
+}
+
+impl Corruption {
+    fn random(rng: &mut impl Rng) -> Self {
+        match rng.random_range(0..5) {
+            0 => Self::Rho(rng.random_range(0..RHO_FE_LEN)),
+            1 => Self::OneTimeSig(
+                rng.random_range(0..NUM_CHUNKS),
+                rng.random_range(0..HASH_FE_LEN),
+            ),
+            2 => Self::MerkleSibling(
+                rng.random_range(0..LOG_LIFETIME),
+                rng.random_range(0..HASH_FE_LEN),
+            ),
+            3 => Self::Parameter(rng.random_range(0..PARAM_FE_LEN)),
+            _ => Self::MerkleRoot(rng.random_range(0..HASH_FE_LEN)),
+        }
+    }
+
+    fn apply(self, pk: &mut PublicKey, sig: &mut Signature, delta: F) {
+        let cell = match self {
+            Self::Rho(i) => &mut sig.rho[i],
+            Self::OneTimeSig(i, j) => &mut sig.one_time_sig[i][j],
+            Self::MerkleSibling(i, j) => &mut sig.merkle_siblings[i][j],
+            Self::Parameter(i) => &mut pk.parameter[i],
+            Self::MerkleRoot(i) => &mut pk.merkle_root[i],
+        };
+        *cell += delta;
+    }
+}
+
+fn env_or<T: core::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[test]
+fn differential() {
+    let seed = env_or("FUZZ_SEED", 0);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let vi: VerificationInput = mock_vi_with_seed(NUM_SIGS, seed);
+    for _ in 0..env_or("FUZZ_ITERATIONS", 16) {
+        let mut vi = vi.clone();
+        // Mostly-valid inputs, with a single field corrupted in 3 out of 4 iterations.
+        let corruption = rng.random_bool(0.75).then(|| {
+            let corruption = Corruption::random(&mut rng);
+            let (pk, sig) = &mut vi.pairs[rng.random_range(0..NUM_SIGS)];
+            corruption.apply(pk, sig, F::from_u32(rng.random_range(1..MODULUS)));
+            corruption
+        });
+
+        let native = vi
+            .pairs
+            .iter()
+            .all(|(pk, sig)| Poseidon2TargetSum::verify(vi.epoch, vi.msg, *pk, *sig).is_ok());
+        let prover_inputs = generate_prover_inputs(0, vi);
+        let air = failed_constraints(&prover_inputs).is_empty()
+            && unbalanced_interactions(&prover_inputs).is_empty();
+        assert_eq!(
+            native, air,
+            "native verifier and AIRs disagree on corruption {corruption:?}"
+        );
+    }
+}
+
+#[test]
+fn excess_chain_steps() {
+    let seed = env_or("FUZZ_SEED", 0);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut vi: VerificationInput = mock_vi_with_seed(NUM_SIGS, seed);
+    let encoded_msg = encode_msg(vi.msg);
+    let (pk, sig) = &mut vi.pairs[0];
+    // Resample `rho` until chunks of the message hash take more than `TARGET_SUM` chain steps.
+    loop {
+        sig.rho = from_fn(|_| F::from_u32(rng.random_range(0..MODULUS)));
+        let trace = VerificationTrace::generate(vi.epoch, encoded_msg, *pk, *sig);
+        let steps = trace
+            .x
+            .iter()
+            .map(|x_i| usize::from((1 << CHUNK_SIZE) - 1 - x_i))
+            .sum::<usize>();
+        if steps > usize::from(TARGET_SUM) {
+            break;
+        }
+    }
+
+    assert!(Poseidon2TargetSum::verify(vi.epoch, vi.msg, *pk, *sig).is_err());
+    let prover_inputs = generate_prover_inputs(0, vi);
+    assert!(
+        !failed_constraints(&prover_inputs).is_empty()
+            || !unbalanced_interactions(&prover_inputs).is_empty()
+    );
+}
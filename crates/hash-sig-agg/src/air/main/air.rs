@@ -1,17 +1,49 @@
 use crate::{
     air::{
-        Bus,
-        main::column::{MainCols, NUM_MAIN_COLS},
+        BusInteractionBuilder, DecompositionMessage, MerkleRootAndMsgHashMessage, ParameterMessage,
+        main::{
+            column::{MainCols, NUM_MAIN_COLS},
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        },
     },
-    hash_sig::F,
+    hash_sig::{
+        F, HALF_FULL_ROUNDS, HASH_FE_LEN, MSG_HASH_FE_LEN, Poseidon2LinearLayers, RC24,
+        SBOX_DEGREE, SBOX_REGISTERS, SIGNER_FE_LEN,
+    },
+    util::zip,
 };
-use core::{borrow::Borrow, iter};
-use p3_air::{Air, AirBuilder, BaseAir, BaseAirWithPublicValues};
-use p3_air_ext::InteractionBuilder;
+use core::{array::from_fn, borrow::Borrow};
+use itertools::Itertools;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
+use p3_air_ext::{InteractionBuilder, SubAirBuilder};
+use p3_field::Algebra;
 use p3_matrix::Matrix;
+use p3_poseidon2_util::air::Poseidon2Air;
+use std::sync::Arc;
+
+/// Main AIR with one row per signature, which also absorbs signers into the sponge of
+/// [`signer_commitment`](crate::hash_sig::signer_commitment) and exposes the commitment as public
+/// values.
+#[derive(Clone, Debug)]
+pub struct MainAir(
+    Arc<
+        Poseidon2Air<
+            F,
+            Poseidon2LinearLayers<WIDTH>,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        >,
+    >,
+);
 
-#[derive(Clone, Copy, Debug, Default)]
-pub struct MainAir;
+impl Default for MainAir {
+    fn default() -> Self {
+        Self(Arc::new(Poseidon2Air::new(RC24.into())))
+    }
+}
 
 impl BaseAir<F> for MainAir {
     fn width(&self) -> usize {
@@ -19,11 +51,16 @@ impl BaseAir<F> for MainAir {
     }
 }
 
-impl BaseAirWithPublicValues<F> for MainAir {}
+impl BaseAirWithPublicValues<F> for MainAir {
+    fn num_public_values(&self) -> usize {
+        HASH_FE_LEN
+    }
+}
 
 impl<AB> Air<AB> for MainAir
 where
-    AB: InteractionBuilder<F = F>,
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+    AB::Expr: Algebra<F>,
 {
     #[inline]
     fn eval(&self, builder: &mut AB) {
@@ -35,6 +72,8 @@ where
         let next: &MainCols<AB::Var> = (*next).borrow();
 
         if !AB::ONLY_INTERACTION {
+            self.0
+                .eval(&mut SubAirBuilder::new(builder, 0, self.0.width()));
             eval_constriants(builder, local, next);
         }
 
@@ -48,18 +87,78 @@ where
 #[inline]
 fn eval_constriants<AB>(builder: &mut AB, local: &MainCols<AB::Var>, next: &MainCols<AB::Var>)
 where
-    AB: AirBuilder<F = F>,
+    AB: AirBuilderWithPublicValues<F = F>,
 {
+    let mut public_values = builder.public_values().iter().copied().map_into();
+    let signer_commitment: [AB::Expr; HASH_FE_LEN] = from_fn(|_| public_values.next().unwrap());
+
     // When every rows
     local.is_active.eval_every_row(builder);
 
     // When first row
-    builder.when_first_row().assert_one(*local.is_active);
+    {
+        let mut builder = builder.when_first_row();
+
+        builder.assert_one(*local.is_active);
+        zip!(&local.perm.inputs[..SIGNER_FE_LEN], local.signer())
+            .for_each(|(input, value)| builder.assert_eq(*input, value));
+        local.perm.inputs[SIGNER_FE_LEN..]
+            .iter()
+            .for_each(|input| builder.assert_zero(*input));
+    }
 
     // When transition
-    local
-        .is_active
-        .eval_transition(&mut builder.when_transition(), &next.is_active);
+    {
+        let mut builder = builder.when_transition();
+
+        local
+            .is_active
+            .eval_transition(&mut builder, &next.is_active);
+        eval_absorb_transition(&mut builder, local, next);
+        eval_squeeze(
+            &mut builder.when((*local.is_active).into() - (*next.is_active).into()),
+            signer_commitment.clone(),
+            local,
+        );
+    }
+
+    // When last row
+    eval_squeeze(
+        &mut builder.when_last_row().when(*local.is_active),
+        signer_commitment,
+        local,
+    );
+}
+
+#[inline]
+fn eval_absorb_transition<AB>(builder: &mut AB, local: &MainCols<AB::Var>, next: &MainCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    let mut builder = builder.when(*next.is_active);
+
+    let signer = next.signer();
+    zip!(next.perm.inputs, local.sponge_output())
+        .enumerate()
+        .for_each(|(idx, (input, output))| {
+            if let Some(value) = signer.get(idx).copied() {
+                builder.assert_eq(input, output + value.into());
+            } else {
+                builder.assert_eq(input, output);
+            }
+        });
+}
+
+#[inline]
+fn eval_squeeze<AB>(
+    builder: &mut AB,
+    signer_commitment: [AB::Expr; HASH_FE_LEN],
+    local: &MainCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    zip!(&local.sponge_output()[..HASH_FE_LEN], signer_commitment)
+        .for_each(|(output, value)| builder.assert_eq(*output, value));
 }
 
 #[inline]
@@ -67,9 +166,11 @@ fn send_parameter<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_send(
-        Bus::Parameter as usize,
-        iter::once(cols.sig_idx).chain(cols.parameter),
+    builder.send(
+        ParameterMessage {
+            sig_idx: cols.sig_idx,
+            parameter: cols.parameter,
+        },
         *cols.is_active,
     );
 }
@@ -79,12 +180,13 @@ fn send_msg_hash<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_send(
-        Bus::MerkleRootAndMsgHash as usize,
-        iter::once(cols.sig_idx)
-            .chain(cols.parameter)
-            .chain(cols.merkle_root)
-            .chain(cols.msg_hash),
+    builder.send(
+        MerkleRootAndMsgHashMessage {
+            sig_idx: cols.sig_idx,
+            parameter: cols.parameter,
+            merkle_root: cols.merkle_root,
+            msg_hash: cols.msg_hash,
+        },
         *cols.is_active,
     );
 }
@@ -94,9 +196,11 @@ fn send_decomposition<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_send(
-        Bus::Decomposition as usize,
-        iter::once(cols.sig_idx).chain(cols.msg_hash.into_iter().rev()),
+    builder.send(
+        DecompositionMessage {
+            sig_idx: cols.sig_idx,
+            msg_hash_rev: from_fn(|i| cols.msg_hash[MSG_HASH_FE_LEN - 1 - i]),
+        },
         *cols.is_active,
     );
 }
@@ -1,14 +1,24 @@
 use crate::{
+    air::main::poseidon2::{PARTIAL_ROUNDS, WIDTH},
     gadget::lower_rows_filter::LowerRowsFilterCols,
-    hash_sig::{HASH_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN},
+    hash_sig::{
+        HALF_FULL_ROUNDS, HASH_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN, SBOX_DEGREE, SBOX_REGISTERS,
+        SIGNER_FE_LEN,
+    },
     util::AlignBorrow,
 };
-use core::borrow::{Borrow, BorrowMut};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+use p3_poseidon2_util::air::{Poseidon2Cols, outputs};
 
 pub const NUM_MAIN_COLS: usize = size_of::<MainCols<u8>>();
 
 #[repr(C)]
 pub struct MainCols<T> {
+    pub perm:
+        Poseidon2Cols<T, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
     pub is_active: LowerRowsFilterCols<T>,
     pub sig_idx: T,
     pub parameter: [T; PARAM_FE_LEN],
@@ -16,6 +26,30 @@ pub struct MainCols<T> {
     pub msg_hash: [T; MSG_HASH_FE_LEN],
 }
 
+crate::util::column::impl_column_names!(MainCols {
+    perm,
+    is_active,
+    sig_idx,
+    parameter,
+    merkle_root,
+    msg_hash,
+});
+
+impl<T: Copy> MainCols<T> {
+    #[inline]
+    pub fn signer(&self) -> [T; SIGNER_FE_LEN] {
+        from_fn(|i| {
+            i.checked_sub(PARAM_FE_LEN)
+                .map_or_else(|| self.parameter[i], |i| self.merkle_root[i])
+        })
+    }
+
+    #[inline]
+    pub fn sponge_output(&self) -> [T; WIDTH] {
+        *outputs(&self.perm)
+    }
+}
+
 impl<T> AlignBorrow<T> for MainCols<T> {
     const SIZE: usize = NUM_MAIN_COLS;
 }
@@ -1,48 +1,107 @@
 use crate::{
-    air::main::column::{MainCols, NUM_MAIN_COLS},
-    hash_sig::{F, VerificationTrace},
-    util::field::{MaybeUninitField, MaybeUninitFieldSlice},
+    air::main::{
+        column::{MainCols, NUM_MAIN_COLS},
+        poseidon2::{PARTIAL_ROUNDS, WIDTH},
+    },
+    hash_sig::{
+        F, HALF_FULL_ROUNDS, Poseidon2LinearLayers, Poseidon2Parameter, RC24, SBOX_DEGREE,
+        SBOX_REGISTERS, VerificationTrace, signer_commitment_input,
+    },
+    util::{
+        field::{MaybeUninitField, MaybeUninitFieldSlice},
+        par_zip,
+        trace::UninitTrace,
+    },
 };
 use core::mem::MaybeUninit;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use hash_sig_verifier::instantiation::poseidon2::Poseidon2Parameter as _;
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
+use p3_poseidon2_util::air::generate_trace_rows_for_perm;
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    traces.len().next_power_of_two()
+pub const NUM_ROWS_PER_SIG: usize = 1;
+
+pub const fn trace_height(num_sigs: usize) -> usize {
+    num_sigs.next_power_of_two()
 }
 
 pub fn generate_trace(
     extra_capacity_bits: usize,
     traces: &[VerificationTrace],
 ) -> RowMajorMatrix<F> {
-    let height = trace_height(traces);
-    let size = height * NUM_MAIN_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_MAIN_COLS);
-
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<MainCols<MaybeUninit<_>>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
-
-    rows.par_iter_mut().enumerate().for_each(|(sig_idx, row)| {
-        if let Some(trace) = traces.get(sig_idx) {
+    let mut trace = UninitTrace::new(
+        extra_capacity_bits,
+        trace_height(traces.len()),
+        NUM_MAIN_COLS,
+    );
+
+    // SAFETY: `MainCols` is `#[repr(C)]` of `MaybeUninit<F>` with the trace width.
+    let (rows, padding_rows) =
+        unsafe { trace.rows_mut::<MainCols<_>>() }.split_at_mut(traces.len() * NUM_ROWS_PER_SIG);
+
+    join(
+        || generate_sigs_rows(rows, 0, traces, [F::ZERO; WIDTH]),
+        || generate_padding_rows(padding_rows),
+    );
+
+    // SAFETY: All rows are written by `generate_sigs_rows` and `generate_padding_rows`.
+    unsafe { trace.assume_init() }
+}
+
+/// Generates rows of `traces` with signature index starting from `sig_idx_offset`, and returns
+/// sponge state of [`signer_commitment`](crate::hash_sig::signer_commitment) after absorbing
+/// signers of `traces` into `state`.
+pub fn generate_sigs_rows(
+    rows: &mut [MainCols<MaybeUninit<F>>],
+    sig_idx_offset: usize,
+    traces: &[VerificationTrace],
+    state: [F; WIDTH],
+) -> [F; WIDTH] {
+    let mut inputs = Vec::with_capacity(traces.len());
+    let state = traces.iter().fold(state, |state, trace| {
+        let input = signer_commitment_input(state, &trace.pk);
+        inputs.push(input);
+        Poseidon2Parameter::permutation_t24(input)
+    });
+    par_zip!(rows, inputs)
+        .enumerate()
+        .for_each(|(idx, (row, input))| {
+            let trace = &traces[idx];
             row.is_active.populate(true);
-            row.sig_idx.write_usize(sig_idx);
+            row.sig_idx.write_usize(sig_idx_offset + idx);
             row.parameter.fill_from_slice(&trace.pk.parameter);
             row.merkle_root.fill_from_slice(&trace.pk.merkle_root);
             row.msg_hash.fill_from_slice(&trace.msg_hash);
-        } else {
-            row.is_active.populate(false);
-            row.sig_idx.write_zero();
-            row.parameter.fill_zero();
-            row.merkle_root.fill_zero();
-            row.msg_hash.fill_zero();
-        }
-    });
-
-    unsafe { vec.set_len(size) };
+            generate_trace_rows_for_perm::<
+                F,
+                Poseidon2LinearLayers<WIDTH>,
+                WIDTH,
+                SBOX_DEGREE,
+                SBOX_REGISTERS,
+                HALF_FULL_ROUNDS,
+                PARTIAL_ROUNDS,
+            >(&mut row.perm, input, &RC24);
+        });
+    state
+}
 
-    RowMajorMatrix::new(vec, NUM_MAIN_COLS)
+#[inline]
+pub fn generate_padding_rows(rows: &mut [MainCols<MaybeUninit<F>>]) {
+    rows.par_iter_mut().for_each(|row| {
+        row.is_active.populate(false);
+        row.sig_idx.write_zero();
+        row.parameter.fill_zero();
+        row.merkle_root.fill_zero();
+        row.msg_hash.fill_zero();
+        generate_trace_rows_for_perm::<
+            F,
+            Poseidon2LinearLayers<WIDTH>,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        >(&mut row.perm, Default::default(), &RC24);
+    });
 }
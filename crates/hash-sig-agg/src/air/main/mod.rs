@@ -1,28 +1,37 @@
 use crate::{
     air::{HashSigAggAir, HashSigAggInteraction, main::generation::generate_trace},
-    hash_sig::{F, VerificationTrace},
-    util::air_instance::AirInstance,
+    hash_sig::{F, HASH_FE_LEN, VerificationTrace},
+    util::air_instance::{AirDescriptor, AirInstance},
 };
 use p3_matrix::dense::RowMajorMatrix;
 
 mod air;
 mod column;
-mod generation;
+pub(super) mod generation;
+
+mod poseidon2 {
+    pub const WIDTH: usize = 24;
+    pub const PARTIAL_ROUNDS: usize = crate::hash_sig::partial_round::<WIDTH>();
+}
 
 pub use air::*;
 pub use column::*;
 
-pub const fn main_public_values() -> Vec<F> {
-    Vec::new()
+pub fn main_public_values(signer_commitment: [F; HASH_FE_LEN]) -> Vec<F> {
+    signer_commitment.to_vec()
 }
 
 pub(super) struct MainAirInstance<'a> {
+    signer_commitment: [F; HASH_FE_LEN],
     traces: &'a [VerificationTrace],
 }
 
 impl<'a> MainAirInstance<'a> {
-    pub const fn new(traces: &'a [VerificationTrace]) -> Self {
-        Self { traces }
+    pub const fn new(signer_commitment: [F; HASH_FE_LEN], traces: &'a [VerificationTrace]) -> Self {
+        Self {
+            signer_commitment,
+            traces,
+        }
     }
 }
 
@@ -30,10 +39,21 @@ impl AirInstance<F> for MainAirInstance<'_> {
     type Air = HashSigAggAir;
     type Interaction = HashSigAggInteraction;
 
+    fn descriptor(&self) -> AirDescriptor {
+        AirDescriptor {
+            name: "Main",
+            consumes_interaction: false,
+        }
+    }
+
     fn air(&self) -> HashSigAggAir {
         HashSigAggAir::Main(Default::default())
     }
 
+    fn public_values(&self) -> Vec<F> {
+        main_public_values(self.signer_commitment)
+    }
+
     fn generate_trace(
         &self,
         extra_capacity_bits: usize,
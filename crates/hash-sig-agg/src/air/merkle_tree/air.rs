@@ -1,6 +1,6 @@
 use crate::{
     air::{
-        Bus,
+        BusInteractionBuilder, MerkleLeafMessage, MerkleRootAndMsgHashMessage,
         merkle_tree::{
             column::{MerkleTreeCols, NUM_MERKLE_TREE_COLS},
             poseidon2::{PARTIAL_ROUNDS, WIDTH},
@@ -13,7 +13,7 @@ use crate::{
     },
     util::zip,
 };
-use core::{array::from_fn, borrow::Borrow, iter};
+use core::{array::from_fn, borrow::Borrow};
 use itertools::Itertools;
 use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
 use p3_air_ext::{InteractionBuilder, SubAirBuilder};
@@ -401,12 +401,13 @@ fn receive_merkle_root_and_msg_hash<AB>(
 ) where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_receive(
-        Bus::MerkleRootAndMsgHash as usize,
-        iter::once(local.sig_idx.into())
-            .chain(local.merkle_parameter().map(Into::into))
-            .chain(local.compress_output::<AB>())
-            .chain(next.msg_hash::<AB>()),
+    builder.receive(
+        MerkleRootAndMsgHashMessage {
+            sig_idx: local.sig_idx.into(),
+            parameter: local.merkle_parameter().map(Into::into),
+            merkle_root: local.compress_output::<AB>(),
+            msg_hash: next.msg_hash::<AB>(),
+        },
         local.is_last_merkle_path_row::<AB>(),
     );
 }
@@ -419,48 +420,47 @@ fn receive_merkle_leaf<AB>(
 ) where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_receive(
-        Bus::MerkleLeaf as usize,
-        [local.sig_idx.into(), local.leaf_chunk_idx.into()]
-            .into_iter()
-            .chain(local.sponge_block[..HASH_FE_LEN].iter().copied().map_into()),
+    builder.receive(
+        MerkleLeafMessage {
+            sig_idx: local.sig_idx.into(),
+            leaf_chunk_idx: local.leaf_chunk_idx.into(),
+            leaf_chunk: from_fn(|i| local.sponge_block[i].into()),
+        },
         local.is_receive_merkle_tree[0] * local.leaf_chunk_start_ind[0].into(),
     );
-    builder.push_receive(
-        Bus::MerkleLeaf as usize,
-        [
-            local.sig_idx.into(),
-            local.leaf_chunk_idx.into() + local.leaf_chunk_start_ind[0].into(),
-        ]
-        .into_iter()
-        .chain((0..HASH_FE_LEN).map(|i| {
-            (1..)
-                .take(HASH_FE_LEN)
-                .map(|j| local.leaf_chunk_start_ind[j] * local.sponge_block[j + i])
-                .sum()
-        })),
+    builder.receive(
+        MerkleLeafMessage {
+            sig_idx: local.sig_idx.into(),
+            leaf_chunk_idx: local.leaf_chunk_idx.into() + local.leaf_chunk_start_ind[0].into(),
+            leaf_chunk: from_fn(|i| {
+                (1..)
+                    .take(HASH_FE_LEN)
+                    .map(|j| local.leaf_chunk_start_ind[j] * local.sponge_block[j + i])
+                    .sum()
+            }),
+        },
         local.is_receive_merkle_tree[1],
     );
-    builder.push_receive(
-        Bus::MerkleLeaf as usize,
-        [
-            local.sig_idx.into(),
-            local.leaf_chunk_idx.into() + local.leaf_chunk_start_ind[0].into() + AB::Expr::ONE,
-        ]
-        .into_iter()
-        .chain((0..HASH_FE_LEN).map(|i| {
-            (1 + HASH_FE_LEN..)
-                .take(HASH_FE_LEN)
-                .map(|j| {
-                    local.leaf_chunk_start_ind[j]
-                        * (if j + i < SPONGE_RATE {
-                            local.sponge_block[j + i]
-                        } else {
-                            next.sponge_block[j + i - SPONGE_RATE]
-                        })
-                })
-                .sum()
-        })),
+    builder.receive(
+        MerkleLeafMessage {
+            sig_idx: local.sig_idx.into(),
+            leaf_chunk_idx: local.leaf_chunk_idx.into()
+                + local.leaf_chunk_start_ind[0].into()
+                + AB::Expr::ONE,
+            leaf_chunk: from_fn(|i| {
+                (1 + HASH_FE_LEN..)
+                    .take(HASH_FE_LEN)
+                    .map(|j| {
+                        local.leaf_chunk_start_ind[j]
+                            * (if j + i < SPONGE_RATE {
+                                local.sponge_block[j + i]
+                            } else {
+                                next.sponge_block[j + i - SPONGE_RATE]
+                            })
+                    })
+                    .sum()
+            }),
+        },
         local.is_receive_merkle_tree[2] * not(local.is_last_sponge_step::<AB>()),
     );
 }
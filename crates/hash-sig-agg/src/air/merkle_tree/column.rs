@@ -43,6 +43,24 @@ pub struct MerkleTreeCols<T> {
     pub is_right: T,
 }
 
+crate::util::column::impl_column_names!(MerkleTreeCols {
+    perm,
+    sig_idx,
+    is_msg,
+    is_merkle_leaf,
+    is_merkle_leaf_transition,
+    is_merkle_path,
+    is_merkle_path_transition,
+    is_receive_merkle_tree,
+    sponge_step,
+    sponge_block,
+    leaf_chunk_start_ind,
+    leaf_chunk_idx,
+    level,
+    epoch_dec,
+    is_right,
+});
+
 impl<T> MerkleTreeCols<T> {
     #[inline]
     pub const fn as_slice(&self) -> &[T] {
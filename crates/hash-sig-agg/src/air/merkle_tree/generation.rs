@@ -11,19 +11,21 @@ use crate::{
     util::{
         concat_array,
         field::{MaybeUninitField, MaybeUninitFieldSlice},
-        par_zip, zip,
+        par_zip,
+        trace::UninitTrace,
+        zip,
     },
 };
 use core::{array::from_fn, iter, mem::MaybeUninit};
 use p3_field::PrimeCharacteristicRing;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 use p3_poseidon2_util::air::{generate_trace_rows_for_perm, outputs};
 
-const NUM_ROWS_PER_SIG: usize = 1 + SPONGE_PERM + LOG_LIFETIME;
+pub const NUM_ROWS_PER_SIG: usize = 1 + SPONGE_PERM + LOG_LIFETIME;
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    (traces.len() * NUM_ROWS_PER_SIG).next_power_of_two()
+pub const fn trace_height(num_sigs: usize) -> usize {
+    (num_sigs * NUM_ROWS_PER_SIG).next_power_of_two()
 }
 
 pub fn generate_trace(
@@ -32,41 +34,43 @@ pub fn generate_trace(
     encoded_msg: [F; MSG_FE_LEN],
     traces: &[VerificationTrace],
 ) -> RowMajorMatrix<F> {
-    let height = trace_height(traces);
-    let size = height * NUM_MERKLE_TREE_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_MERKLE_TREE_COLS);
-
-    let (prefix, rows, suffix) = unsafe {
-        trace
-            .values
-            .align_to_mut::<MerkleTreeCols<MaybeUninit<F>>>()
-    };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
+    let mut trace = UninitTrace::new(
+        extra_capacity_bits,
+        trace_height(traces.len()),
+        NUM_MERKLE_TREE_COLS,
+    );
 
-    let (rows, padding_rows) = rows.split_at_mut(traces.len() * NUM_ROWS_PER_SIG);
+    // SAFETY: `MerkleTreeCols` is `#[repr(C)]` of `MaybeUninit<F>` with the trace width.
+    let (rows, padding_rows) = unsafe { trace.rows_mut::<MerkleTreeCols<_>>() }
+        .split_at_mut(traces.len() * NUM_ROWS_PER_SIG);
 
     join(
-        || {
-            par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
-                .enumerate()
-                .for_each(|(sig_idx, (rows, trace))| {
-                    let (leaf_rows, rows) = rows.split_at_mut(SPONGE_PERM);
-                    let (msg_row, path_rows) = rows.split_last_mut().unwrap();
-                    let leaf_hash = generate_leaf_rows(leaf_rows, epoch, sig_idx, trace);
-                    generate_path_rows(path_rows, epoch, sig_idx, trace, leaf_hash);
-                    generate_msg_row(msg_row, epoch, encoded_msg, trace, sig_idx);
-                });
-        },
+        || generate_sigs_rows(rows, epoch, encoded_msg, 0, traces),
         || generate_padding_rows(padding_rows),
     );
 
-    unsafe { vec.set_len(size) };
+    // SAFETY: All rows are written by `generate_sigs_rows` and `generate_padding_rows`.
+    unsafe { trace.assume_init() }
+}
 
-    RowMajorMatrix::new(vec, NUM_MERKLE_TREE_COLS)
+/// Generates rows of `traces` with signature index starting from `sig_idx_offset`.
+pub fn generate_sigs_rows(
+    rows: &mut [MerkleTreeCols<MaybeUninit<F>>],
+    epoch: u32,
+    encoded_msg: [F; MSG_FE_LEN],
+    sig_idx_offset: usize,
+    traces: &[VerificationTrace],
+) {
+    par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
+        .enumerate()
+        .for_each(|(idx, (rows, trace))| {
+            let sig_idx = sig_idx_offset + idx;
+            let (leaf_rows, rows) = rows.split_at_mut(SPONGE_PERM);
+            let (msg_row, path_rows) = rows.split_last_mut().unwrap();
+            let leaf_hash = generate_leaf_rows(leaf_rows, epoch, sig_idx, trace);
+            generate_path_rows(path_rows, epoch, sig_idx, trace, leaf_hash);
+            generate_msg_row(msg_row, epoch, encoded_msg, trace, sig_idx);
+        });
 }
 
 #[inline]
@@ -125,6 +129,7 @@ fn generate_leaf_rows(
                 HALF_FULL_ROUNDS,
                 PARTIAL_ROUNDS,
             >(&mut row.perm, input, &RC24);
+            // SAFETY: Outputs are written by `generate_trace_rows_for_perm`.
             unsafe { from_fn(|i| outputs(&row.perm)[i].assume_init()) }
         });
     from_fn(|i| output[i])
@@ -181,6 +186,7 @@ fn generate_path_rows(
                 PARTIAL_ROUNDS,
             >(&mut row.perm, input, &RC24);
             epoch_dec >>= 1;
+            // SAFETY: Outputs are written by `generate_trace_rows_for_perm`.
             unsafe { from_fn(|i| input[i] + outputs(&row.perm)[i].assume_init()) }
         },
     );
@@ -221,7 +227,7 @@ fn generate_msg_row(
 }
 
 #[inline]
-fn generate_padding_rows(rows: &mut [MerkleTreeCols<MaybeUninit<F>>]) {
+pub fn generate_padding_rows(rows: &mut [MerkleTreeCols<MaybeUninit<F>>]) {
     if let Some((template, rows)) = rows.split_first_mut() {
         generate_padding_row(template);
         let template = template.as_slice();
@@ -1,7 +1,7 @@
 use crate::{
     air::{HashSigAggAir, HashSigAggInteraction, merkle_tree::generation::generate_trace},
     hash_sig::{F, MSG_FE_LEN, VerificationTrace, encode_tweak_merkle_tree, encode_tweak_msg},
-    util::air_instance::AirInstance,
+    util::air_instance::{AirDescriptor, AirInstance},
 };
 use core::iter;
 use p3_field::PrimeCharacteristicRing;
@@ -9,7 +9,7 @@ use p3_matrix::dense::RowMajorMatrix;
 
 mod air;
 mod column;
-mod generation;
+pub(super) mod generation;
 
 mod poseidon2 {
     pub const WIDTH: usize = 24;
@@ -43,6 +43,13 @@ impl AirInstance<F> for MerkleTreeAirInstance<'_> {
     type Air = HashSigAggAir;
     type Interaction = HashSigAggInteraction;
 
+    fn descriptor(&self) -> AirDescriptor {
+        AirDescriptor {
+            name: "MerkleTree",
+            consumes_interaction: false,
+        }
+    }
+
     fn air(&self) -> HashSigAggAir {
         HashSigAggAir::MerkleTree(Default::default())
     }
@@ -1,18 +1,28 @@
 use crate::{
     air::{
-        chain::{ChainAir, ChainAirInstance},
-        decomposition::{DecompositionAir, DecompositionAirInstance},
-        main::{MainAir, MainAirInstance},
-        merkle_tree::{MerkleTreeAir, MerkleTreeAirInstance},
-        range_check::{RangeCheckAir, RangeCheckAirInstance},
+        chain::{ChainAir, ChainCols, NUM_CHAIN_COLS},
+        decomposition::{DecompositionAir, DecompositionCols, NUM_DECOMPOSITION_COLS},
+        main::{MainAir, MainCols, NUM_MAIN_COLS},
+        merkle_tree::{MerkleTreeAir, MerkleTreeCols, NUM_MERKLE_TREE_COLS},
+        range_check::{RangeCheckAir, RangeCheckCols},
     },
-    hash_sig::{F, MSG_LEN, VerificationInput, VerificationTrace, encode_msg},
-    util::air_instance::AirInstance,
+    hash_sig::{
+        F, HASH_FE_LEN, MSG_FE_LEN, MSG_LEN, PublicKey, Signature, VerificationInput,
+        VerificationTrace, encode_msg,
+    },
+    util::trace::UninitTrace,
+};
+use core::{
+    array::from_fn,
+    iter,
+    mem::{self, MaybeUninit},
 };
-use p3_air::{Air, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
-use p3_air_ext::{InteractionBuilder, ProverInput, VerifierInput};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
+use p3_air_ext::{InteractionBuilder, ProverInput, SymbolicAirBuilder, VerifierInput};
+use p3_field::PrimeCharacteristicRing;
 use p3_maybe_rayon::prelude::*;
 use range_check::RangeCheckInteraction;
+use std::collections::BTreeMap;
 use tracing::instrument;
 
 pub mod chain;
@@ -21,31 +31,87 @@ pub mod main;
 pub mod merkle_tree;
 pub mod range_check;
 
-#[repr(u8)]
-enum Bus {
-    Parameter,
-    MerkleRootAndMsgHash,
-    Chain,
-    MerkleLeaf,
-    Decomposition,
-    RangeCheck,
-}
+pub mod debug;
+pub mod export;
+pub mod stats;
+pub mod symbolic;
+
+mod bus;
+#[cfg(test)]
+mod fuzz;
+mod registry;
+#[cfg(test)]
+mod tamper;
 
+pub use crate::util::{
+    air_instance::{AirDescriptor, AirInstance},
+    column::{Col, ColumnNames},
+};
+pub use bus::*;
+pub use registry::*;
+
+/// Maximum degree of constraints of all AIRs.
+pub const MAX_CONSTRAINT_DEGREE: usize = 3;
+
+/// Interaction collected by trace generation of AIRs and consumed by others.
 #[derive(Default)]
-struct HashSigAggInteraction {
-    range_check: RangeCheckInteraction,
+pub struct HashSigAggInteraction {
+    pub range_check: RangeCheckInteraction,
 }
 
+/// All AIRs, where `T` is AIR added by downstream via [`AirRegistry`].
 #[derive(Clone, Debug)]
-pub enum HashSigAggAir {
+pub enum HashSigAggAir<T = NoExtension> {
     Chain(ChainAir),
     Decomposition(DecompositionAir),
     Main(MainAir),
     MerkleTree(MerkleTreeAir),
     RangeCheck(RangeCheckAir),
+    Extension(T),
+}
+
+impl<T: ExtensionAir> HashSigAggAir<T> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Chain(_) => "Chain",
+            Self::Decomposition(_) => "Decomposition",
+            Self::Main(_) => "Main",
+            Self::MerkleTree(_) => "MerkleTree",
+            Self::RangeCheck(_) => "RangeCheck",
+            Self::Extension(air) => air.name(),
+        }
+    }
 }
 
-impl BaseAir<F> for HashSigAggAir {
+impl<T: BaseAir<F>> HashSigAggAir<T> {
+    /// Returns names of columns of main trace, where columns of [`HashSigAggAir::Extension`] are
+    /// named by index.
+    pub fn column_names(&self) -> Vec<String> {
+        match self {
+            Self::Chain(_) => ChainCols::<Col>::column_names(),
+            Self::Decomposition(_) => DecompositionCols::<Col>::column_names(),
+            Self::Main(_) => MainCols::<Col>::column_names(),
+            Self::MerkleTree(_) => MerkleTreeCols::<Col>::column_names(),
+            Self::RangeCheck(_) => RangeCheckCols::<Col>::column_names(),
+            Self::Extension(air) => (0..air.width()).map(|idx| format!("main[{idx}]")).collect(),
+        }
+    }
+}
+
+impl HashSigAggAir {
+    fn into_extended<T>(self) -> HashSigAggAir<T> {
+        match self {
+            Self::Chain(air) => HashSigAggAir::Chain(air),
+            Self::Decomposition(air) => HashSigAggAir::Decomposition(air),
+            Self::Main(air) => HashSigAggAir::Main(air),
+            Self::MerkleTree(air) => HashSigAggAir::MerkleTree(air),
+            Self::RangeCheck(air) => HashSigAggAir::RangeCheck(air),
+            Self::Extension(air) => match air {},
+        }
+    }
+}
+
+impl<T: BaseAir<F>> BaseAir<F> for HashSigAggAir<T> {
     fn width(&self) -> usize {
         match self {
             Self::Chain(air) => air.width(),
@@ -53,11 +119,12 @@ impl BaseAir<F> for HashSigAggAir {
             Self::Main(air) => air.width(),
             Self::MerkleTree(air) => air.width(),
             Self::RangeCheck(air) => air.width(),
+            Self::Extension(air) => air.width(),
         }
     }
 }
 
-impl BaseAirWithPublicValues<F> for HashSigAggAir {
+impl<T: BaseAirWithPublicValues<F>> BaseAirWithPublicValues<F> for HashSigAggAir<T> {
     fn num_public_values(&self) -> usize {
         match self {
             Self::Chain(air) => air.num_public_values(),
@@ -65,13 +132,15 @@ impl BaseAirWithPublicValues<F> for HashSigAggAir {
             Self::Main(air) => air.num_public_values(),
             Self::MerkleTree(air) => air.num_public_values(),
             Self::RangeCheck(air) => air.num_public_values(),
+            Self::Extension(air) => air.num_public_values(),
         }
     }
 }
 
-impl<AB> Air<AB> for HashSigAggAir
+impl<AB, T> Air<AB> for HashSigAggAir<T>
 where
     AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+    T: Air<AB>,
 {
     #[inline]
     fn eval(&self, builder: &mut AB) {
@@ -81,10 +150,41 @@ where
             Self::Main(air) => air.eval(builder),
             Self::MerkleTree(air) => air.eval(builder),
             Self::RangeCheck(air) => air.eval(builder),
+            Self::Extension(air) => air.eval(builder),
         }
     }
 }
 
+/// AIR added by downstream via [`AirRegistry`] as [`HashSigAggAir::Extension`].
+pub trait ExtensionAir {
+    /// Returns name of the AIR, which should be unique among AIRs in the same registry.
+    fn name(&self) -> &'static str;
+}
+
+/// Uninhabited AIR for [`HashSigAggAir`] without extension.
+#[derive(Clone, Copy, Debug)]
+pub enum NoExtension {}
+
+impl ExtensionAir for NoExtension {
+    fn name(&self) -> &'static str {
+        match *self {}
+    }
+}
+
+impl BaseAir<F> for NoExtension {
+    fn width(&self) -> usize {
+        match *self {}
+    }
+}
+
+impl BaseAirWithPublicValues<F> for NoExtension {}
+
+impl<AB: AirBuilder> Air<AB> for NoExtension {
+    fn eval(&self, _: &mut AB) {
+        match *self {}
+    }
+}
+
 #[instrument(name = "generate hash-sig aggregation traces", skip_all)]
 pub fn generate_prover_inputs(
     extra_capacity_bits: usize,
@@ -96,70 +196,350 @@ pub fn generate_prover_inputs(
         .into_par_iter()
         .map(|(pk, sig)| VerificationTrace::generate(vi.epoch, encoded_msg, pk, sig))
         .collect::<Vec<_>>();
+    generate_prover_inputs_from_traces(extra_capacity_bits, vi.epoch, encoded_msg, &traces)
+}
 
-    let chain = ChainAirInstance::new(vi.epoch, &traces);
-    let decomposition = DecompositionAirInstance::new(&traces);
-    let main = MainAirInstance::new(&traces);
-    let merkle_tree = MerkleTreeAirInstance::new(vi.epoch, encoded_msg, &traces);
-    let range_check = RangeCheckAirInstance::new();
-    let interaction = Default::default();
-
-    let (
-        (chain_prover_input, main_prover_input),
-        (merkle_tree_prover_input, decomposition_prover_input),
-    ) = join(
-        || {
-            join(
-                || chain.prover_input(extra_capacity_bits, &interaction),
-                || main.prover_input(extra_capacity_bits, &interaction),
-            )
-        },
-        || {
-            join(
-                || merkle_tree.prover_input(extra_capacity_bits, &interaction),
-                || decomposition.prover_input(extra_capacity_bits, &interaction),
-            )
-        },
-    );
-    let range_check_prover_input = range_check.prover_input(extra_capacity_bits, &interaction);
+/// Returns prover inputs from already generated `traces`, which are expected to be generated
+/// with the same `epoch` and `encoded_msg`.
+pub fn generate_prover_inputs_from_traces(
+    extra_capacity_bits: usize,
+    epoch: u32,
+    encoded_msg: [F; MSG_FE_LEN],
+    traces: &[VerificationTrace],
+) -> Vec<ProverInput<F, HashSigAggAir>> {
+    AirRegistry::builtin(epoch, encoded_msg, traces).prover_inputs(extra_capacity_bits)
+}
 
-    vec![
-        chain_prover_input,
-        decomposition_prover_input,
-        main_prover_input,
-        merkle_tree_prover_input,
-        range_check_prover_input,
-    ]
+/// Returns prover inputs like [`generate_prover_inputs`], but generates [`VerificationTrace`]s in
+/// chunks and writes them into the AIR traces directly, so at most `memory_budget` bytes of
+/// [`VerificationTrace`]s are alive at a time on top of the AIR traces themselves.
+pub fn generate_prover_inputs_with_memory_budget(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+    memory_budget: usize,
+) -> Vec<ProverInput<F, HashSigAggAir>> {
+    generate_prover_inputs_from_pairs(
+        &AirRegistry::builtin(vi.epoch, encode_msg(vi.msg), &[]),
+        extra_capacity_bits,
+        vi.epoch,
+        vi.msg,
+        vi.pairs.len(),
+        vi.pairs,
+        memory_budget,
+    )
+    .unwrap()
 }
 
-pub fn verifier_inputs(epoch: u32, msg: [u8; MSG_LEN]) -> Vec<VerifierInput<F, HashSigAggAir>> {
+/// Returns prover inputs like [`generate_prover_inputs_with_memory_budget`], but takes `num_sigs`
+/// pairs from `pairs` chunk by chunk, so pairs can be streamed from e.g. a dataset file without
+/// being loaded at once.
+///
+/// The `registry` is expected to be [`AirRegistry::builtin`] of the same `epoch` and `msg` without
+/// traces, optionally with extension AIRs registered, and traces of AIRs other than the builtin
+/// ones with fixed amount of rows per signature are generated by the `registry`. Public values of
+/// `Main` are the signer commitment of `pairs` instead of the one in `registry`.
+///
+/// Returns error if `pairs` doesn't have exactly `num_sigs` items.
+#[instrument(name = "generate hash-sig aggregation traces", skip_all)]
+pub fn generate_prover_inputs_from_pairs<'a, T: 'a>(
+    registry: &AirRegistry<'a, T>,
+    extra_capacity_bits: usize,
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    num_sigs: usize,
+    pairs: impl IntoIterator<Item = (PublicKey, Signature)>,
+    memory_budget: usize,
+) -> Result<Vec<ProverInput<F, HashSigAggAir<T>>>, String>
+where
+    T: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>> + Send,
+{
+    let chunk_size = (memory_budget / size_of::<VerificationTrace>()).max(1);
     let encoded_msg = encode_msg(msg);
+    let interaction = HashSigAggInteraction::default();
+
+    let mut chain_trace = UninitTrace::new(
+        extra_capacity_bits,
+        chain::generation::trace_height(num_sigs),
+        NUM_CHAIN_COLS,
+    );
+    let mut decomposition_trace = UninitTrace::new(
+        extra_capacity_bits,
+        decomposition::generation::trace_height(num_sigs),
+        NUM_DECOMPOSITION_COLS,
+    );
+    let mut main_trace = UninitTrace::new(
+        extra_capacity_bits,
+        main::generation::trace_height(num_sigs),
+        NUM_MAIN_COLS,
+    );
+    let mut merkle_tree_trace = UninitTrace::new(
+        extra_capacity_bits,
+        merkle_tree::generation::trace_height(num_sigs),
+        NUM_MERKLE_TREE_COLS,
+    );
 
-    let chain = ChainAirInstance::new(epoch, &[]);
-    let decomposition = DecompositionAirInstance::new(&[]);
-    let main = MainAirInstance::new(&[]);
-    let merkle_tree = MerkleTreeAirInstance::new(epoch, encoded_msg, &[]);
-    let range_check = RangeCheckAirInstance::new();
+    // SAFETY: Each of the column structs is `#[repr(C)]` of `MaybeUninit<F>` with the trace width.
+    let (rows, padding_rows) = unsafe {
+        SigRows {
+            chain: chain_trace.rows_mut(),
+            decomposition: decomposition_trace.rows_mut(),
+            main: main_trace.rows_mut(),
+            merkle_tree: merkle_tree_trace.rows_mut(),
+        }
+    }
+    .split_at(num_sigs);
+    padding_rows.generate_padding_rows();
+    let mut pairs = pairs.into_iter();
+    let signer_commitment_state = rows.chunks(chunk_size).enumerate().try_fold(
+        [F::ZERO; 24],
+        |signer_commitment_state, (chunk_idx, rows)| {
+            let pairs = pairs.by_ref().take(rows.main.len()).collect::<Vec<_>>();
+            if pairs.len() != rows.main.len() {
+                return Err(format!("pairs have less than {num_sigs} items"));
+            }
+            let traces = pairs
+                .into_par_iter()
+                .map(|(pk, sig)| VerificationTrace::generate(epoch, encoded_msg, pk, sig))
+                .collect::<Vec<_>>();
+            Ok(rows.generate(
+                epoch,
+                encoded_msg,
+                chunk_idx * chunk_size,
+                &traces,
+                &interaction,
+                signer_commitment_state,
+            ))
+        },
+    )?;
+    if pairs.next().is_some() {
+        return Err(format!("pairs have more than {num_sigs} items"));
+    }
+    let signer_commitment: [_; HASH_FE_LEN] = from_fn(|i| signer_commitment_state[i]);
 
-    vec![
-        chain.verifier_input(),
-        decomposition.verifier_input(),
-        main.verifier_input(),
-        merkle_tree.verifier_input(),
-        range_check.verifier_input(),
-    ]
+    // SAFETY: All rows are written by `generate_padding_rows` and `generate` of each chunk.
+    let traces = unsafe {
+        BTreeMap::from([
+            ("Chain", chain_trace.assume_init()),
+            ("Decomposition", decomposition_trace.assume_init()),
+            ("Main", main_trace.assume_init()),
+            ("MerkleTree", merkle_tree_trace.assume_init()),
+        ])
+    };
+    let public_values = BTreeMap::from([("Main", main::main_public_values(signer_commitment))]);
+    Ok(registry.prover_inputs_with_traces(
+        extra_capacity_bits,
+        &interaction,
+        traces,
+        &public_values,
+    ))
+}
+
+/// Rows of AIRs which have fixed amount of rows per signature.
+#[derive(Default)]
+struct SigRows<'a> {
+    chain: &'a mut [ChainCols<MaybeUninit<F>>],
+    decomposition: &'a mut [DecompositionCols<MaybeUninit<F>>],
+    main: &'a mut [MainCols<MaybeUninit<F>>],
+    merkle_tree: &'a mut [MerkleTreeCols<MaybeUninit<F>>],
+}
+
+impl<'a> SigRows<'a> {
+    fn split_at(self, num_sigs: usize) -> (Self, Self) {
+        let (chain, chain_rest) = self
+            .chain
+            .split_at_mut(num_sigs * chain::generation::NUM_ROWS_PER_SIG);
+        let (decomposition, decomposition_rest) = self
+            .decomposition
+            .split_at_mut(num_sigs * decomposition::generation::NUM_ROWS_PER_SIG);
+        let (main, main_rest) = self
+            .main
+            .split_at_mut(num_sigs * main::generation::NUM_ROWS_PER_SIG);
+        let (merkle_tree, merkle_tree_rest) = self
+            .merkle_tree
+            .split_at_mut(num_sigs * merkle_tree::generation::NUM_ROWS_PER_SIG);
+        (
+            Self {
+                chain,
+                decomposition,
+                main,
+                merkle_tree,
+            },
+            Self {
+                chain: chain_rest,
+                decomposition: decomposition_rest,
+                main: main_rest,
+                merkle_tree: merkle_tree_rest,
+            },
+        )
+    }
+
+    fn chunks(mut self, num_sigs: usize) -> impl Iterator<Item = SigRows<'a>> {
+        iter::from_fn(move || {
+            if self.main.is_empty() {
+                return None;
+            }
+            let num_sigs = num_sigs.min(self.main.len());
+            let (chunk, rest) = mem::take(&mut self).split_at(num_sigs);
+            self = rest;
+            Some(chunk)
+        })
+    }
+
+    fn generate_padding_rows(self) {
+        join(
+            || {
+                join(
+                    || chain::generation::generate_padding_rows(self.chain),
+                    || decomposition::generation::generate_padding_rows(self.decomposition),
+                )
+            },
+            || {
+                join(
+                    || main::generation::generate_padding_rows(self.main),
+                    || merkle_tree::generation::generate_padding_rows(self.merkle_tree),
+                )
+            },
+        );
+    }
+
+    /// Generates rows of `traces`, and returns sponge state of signer commitment after absorbing
+    /// signers of `traces` into `signer_commitment_state`.
+    fn generate(
+        self,
+        epoch: u32,
+        encoded_msg: [F; MSG_FE_LEN],
+        sig_idx_offset: usize,
+        traces: &[VerificationTrace],
+        interaction: &HashSigAggInteraction,
+        signer_commitment_state: [F; 24],
+    ) -> [F; 24] {
+        let (_, (signer_commitment_state, ())) = join(
+            || {
+                join(
+                    || chain::generation::generate_sigs_rows(self.chain, sig_idx_offset, traces),
+                    || {
+                        decomposition::generation::generate_sigs_rows(
+                            self.decomposition,
+                            sig_idx_offset,
+                            traces,
+                            &interaction.range_check,
+                        );
+                    },
+                )
+            },
+            || {
+                join(
+                    || {
+                        main::generation::generate_sigs_rows(
+                            self.main,
+                            sig_idx_offset,
+                            traces,
+                            signer_commitment_state,
+                        )
+                    },
+                    || {
+                        merkle_tree::generation::generate_sigs_rows(
+                            self.merkle_tree,
+                            epoch,
+                            encoded_msg,
+                            sig_idx_offset,
+                            traces,
+                        );
+                    },
+                )
+            },
+        );
+        signer_commitment_state
+    }
+}
+
+/// Returns verifier inputs of aggregation of signers committed by `signer_commitment`, see
+/// [`signer_commitment`](crate::hash_sig::signer_commitment).
+pub fn verifier_inputs(
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    signer_commitment: [F; HASH_FE_LEN],
+) -> Vec<VerifierInput<F, HashSigAggAir>> {
+    AirRegistry::builtin_with_signer_commitment(epoch, encode_msg(msg), signer_commitment, &[])
+        .verifier_inputs()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::air::generate_prover_inputs;
-    use hash_sig_testdata::mock_vi;
+    use crate::{
+        air::{
+            AirRegistry, generate_prover_inputs, generate_prover_inputs_from_pairs,
+            generate_prover_inputs_with_memory_budget,
+        },
+        hash_sig::{VerificationTrace, encode_msg},
+        util::zip,
+    };
+    use hash_sig_testdata::{
+        dataset::{DatasetReader, write},
+        mock_vi, mock_vi_seed, mock_vi_with_seed,
+    };
     use p3_air_ext::check_constraints;
+    use std::io::Cursor;
+
+    #[test]
+    fn airs_with_memory_budget() {
+        let vi = mock_vi(1 << 5);
+        let memory_budget = 3 * size_of::<VerificationTrace>();
+        let prover_inputs = generate_prover_inputs_with_memory_budget(0, vi.clone(), memory_budget);
+        check_constraints(&prover_inputs);
+        zip!(prover_inputs, generate_prover_inputs(0, vi)).for_each(|(lhs, rhs)| {
+            assert_eq!(lhs.trace(), rhs.trace());
+        });
+    }
+
+    #[test]
+    fn airs_from_dataset() {
+        let vi = mock_vi(1 << 5);
+        let mut bytes = Vec::new();
+        write(&mut bytes, &vi, true).unwrap();
+        let reader = DatasetReader::new(Cursor::new(bytes)).unwrap();
+        let num_sigs = reader.header().count as usize;
+        let prover_inputs = generate_prover_inputs_from_pairs(
+            &AirRegistry::builtin(vi.epoch, encode_msg(vi.msg), &[]),
+            0,
+            vi.epoch,
+            vi.msg,
+            num_sigs,
+            reader.map(Result::unwrap),
+            3 * size_of::<VerificationTrace>(),
+        )
+        .unwrap();
+        zip!(prover_inputs, generate_prover_inputs(0, vi)).for_each(|(lhs, rhs)| {
+            assert_eq!(lhs.public_values(), rhs.public_values());
+            assert_eq!(lhs.trace(), rhs.trace());
+        });
+    }
+
+    #[test]
+    fn unmatched_num_sigs() {
+        let vi = mock_vi(4);
+        let registry = AirRegistry::builtin(vi.epoch, encode_msg(vi.msg), &[]);
+        for num_sigs in [3, 5] {
+            assert!(
+                generate_prover_inputs_from_pairs(
+                    &registry,
+                    0,
+                    vi.epoch,
+                    vi.msg,
+                    num_sigs,
+                    vi.pairs.clone(),
+                    size_of::<VerificationTrace>(),
+                )
+                .is_err()
+            );
+        }
+    }
 
     #[test]
     fn airs() {
+        let seed = mock_vi_seed();
         for log_sigs in 1..8 {
-            let vi = mock_vi(1 << log_sigs);
+            let vi = mock_vi_with_seed(1 << log_sigs, seed);
             let prover_inputs = generate_prover_inputs(0, vi);
             check_constraints(&prover_inputs);
         }
@@ -1,6 +1,6 @@
 use crate::{
     air::{
-        Bus,
+        BusInteractionBuilder, RangeCheckMessage,
         range_check::column::{NUM_RANGE_CHECK_COLS, RangeCheckCols},
     },
     hash_sig::F,
@@ -83,5 +83,5 @@ fn receive_range_check<AB>(builder: &mut AB, cols: &RangeCheckCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_receive(Bus::RangeCheck as usize, [cols.value], cols.mult);
+    builder.receive(RangeCheckMessage { value: cols.value }, cols.mult);
 }
@@ -9,6 +9,8 @@ pub struct RangeCheckCols<T> {
     pub mult: T,
 }
 
+crate::util::column::impl_column_names!(RangeCheckCols { value, mult });
+
 impl<T> AlignBorrow<T> for RangeCheckCols<T> {
     const SIZE: usize = NUM_RANGE_CHECK_COLS;
 }
@@ -7,10 +7,10 @@ use crate::{
         },
     },
     hash_sig::F,
-    util::{field::MaybeUninitField, par_zip},
+    util::{field::MaybeUninitField, par_zip, trace::UninitTrace},
 };
 use core::{mem::MaybeUninit, sync::atomic::Ordering};
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 
 pub const fn trace_height() -> usize {
@@ -21,20 +21,9 @@ pub fn generate_trace(
     extra_capacity_bits: usize,
     mult: &RangeCheckInteraction,
 ) -> RowMajorMatrix<F> {
-    let height = trace_height();
-    let size = height * NUM_RANGE_CHECK_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_RANGE_CHECK_COLS);
-
-    let (prefix, rows, suffix) = unsafe {
-        trace
-            .values
-            .align_to_mut::<RangeCheckCols<MaybeUninit<F>>>()
-    };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
+    let mut trace = UninitTrace::new(extra_capacity_bits, trace_height(), NUM_RANGE_CHECK_COLS);
+    // SAFETY: `RangeCheckCols` is `#[repr(C)]` of `MaybeUninit<F>` with the trace width.
+    let rows = unsafe { trace.rows_mut::<RangeCheckCols<MaybeUninit<F>>>() };
 
     par_zip!(rows, &mult.0)
         .enumerate()
@@ -43,7 +32,6 @@ pub fn generate_trace(
             row.mult.write_u32(mult.load(Ordering::Relaxed));
         });
 
-    unsafe { vec.set_len(size) };
-
-    RowMajorMatrix::new(vec, NUM_RANGE_CHECK_COLS)
+    // SAFETY: All rows are written above.
+    unsafe { trace.assume_init() }
 }
@@ -4,7 +4,7 @@ use crate::{
         range_check::generation::generate_trace,
     },
     hash_sig::F,
-    util::air_instance::AirInstance,
+    util::air_instance::{AirDescriptor, AirInstance},
 };
 use core::{
     iter::repeat_with,
@@ -14,7 +14,7 @@ use p3_matrix::dense::RowMajorMatrix;
 
 mod air;
 mod column;
-mod generation;
+pub(super) mod generation;
 
 pub use air::*;
 pub use column::*;
@@ -32,6 +32,13 @@ impl AirInstance<F> for RangeCheckAirInstance {
     type Air = HashSigAggAir;
     type Interaction = HashSigAggInteraction;
 
+    fn descriptor(&self) -> AirDescriptor {
+        AirDescriptor {
+            name: "RangeCheck",
+            consumes_interaction: true,
+        }
+    }
+
     fn air(&self) -> HashSigAggAir {
         HashSigAggAir::RangeCheck(Default::default())
     }
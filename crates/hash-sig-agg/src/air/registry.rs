@@ -0,0 +1,316 @@
+use crate::{
+    air::{
+        AirDescriptor, AirInstance, HashSigAggAir, HashSigAggInteraction, NoExtension,
+        chain::ChainAirInstance, decomposition::DecompositionAirInstance, main::MainAirInstance,
+        merkle_tree::MerkleTreeAirInstance, range_check::RangeCheckAirInstance,
+    },
+    hash_sig::{F, HASH_FE_LEN, MSG_FE_LEN, VerificationTrace, signer_commitment},
+};
+use core::marker::PhantomData;
+use p3_air::{Air, BaseAirWithPublicValues};
+use p3_air_ext::{ProverInput, SymbolicAirBuilder, VerifierInput};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use std::collections::BTreeMap;
+
+type DynAirInstance<'a, T> = dyn AirInstance<F, Air = HashSigAggAir<T>, Interaction = HashSigAggInteraction>
+    + Send
+    + Sync
+    + 'a;
+
+/// Ordered AIR instances to prove together, where AIRs added by downstream are wrapped in
+/// [`HashSigAggAir::Extension`] and talk to builtin AIRs over [`Bus`](crate::air::Bus).
+pub struct AirRegistry<'a, T = NoExtension> {
+    instances: Vec<Box<DynAirInstance<'a, T>>>,
+}
+
+impl<T> Default for AirRegistry<'_, T> {
+    fn default() -> Self {
+        Self {
+            instances: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T: 'a> AirRegistry<'a, T> {
+    /// Returns registry with builtin AIRs for aggregation of `traces`.
+    pub fn builtin(
+        epoch: u32,
+        encoded_msg: [F; MSG_FE_LEN],
+        traces: &'a [VerificationTrace],
+    ) -> Self {
+        let signer_commitment = signer_commitment(traces.iter().map(|trace| &trace.pk));
+        Self::builtin_with_signer_commitment(epoch, encoded_msg, signer_commitment, traces)
+    }
+
+    /// Returns registry like [`Self::builtin`], but with `signer_commitment` given instead of
+    /// derived from `traces`, e.g. for verifier which has no traces.
+    pub fn builtin_with_signer_commitment(
+        epoch: u32,
+        encoded_msg: [F; MSG_FE_LEN],
+        signer_commitment: [F; HASH_FE_LEN],
+        traces: &'a [VerificationTrace],
+    ) -> Self {
+        let mut registry = Self::default();
+        registry
+            .register_builtin(ChainAirInstance::new(epoch, traces))
+            .register_builtin(DecompositionAirInstance::new(traces))
+            .register_builtin(MainAirInstance::new(signer_commitment, traces))
+            .register_builtin(MerkleTreeAirInstance::new(epoch, encoded_msg, traces))
+            .register_builtin(RangeCheckAirInstance::new());
+        registry
+    }
+
+    pub fn register(
+        &mut self,
+        instance: impl AirInstance<F, Air = HashSigAggAir<T>, Interaction = HashSigAggInteraction>
+        + Send
+        + Sync
+        + 'a,
+    ) -> &mut Self {
+        let name = instance.descriptor().name;
+        assert!(
+            self.descriptors().all(|descriptor| descriptor.name != name),
+            "AIR {name} already registered"
+        );
+        self.instances.push(Box::new(instance));
+        self
+    }
+
+    fn register_builtin(
+        &mut self,
+        instance: impl AirInstance<F, Air = HashSigAggAir, Interaction = HashSigAggInteraction>
+        + Send
+        + Sync
+        + 'a,
+    ) -> &mut Self {
+        self.register(BuiltinAirInstance(instance, PhantomData))
+    }
+
+    pub fn descriptors(&self) -> impl Iterator<Item = AirDescriptor> + '_ {
+        self.instances.iter().map(|instance| instance.descriptor())
+    }
+
+    /// Returns prover inputs in order of registration, where traces of AIRs that consume
+    /// interaction are generated after all others.
+    pub fn prover_inputs(&self, extra_capacity_bits: usize) -> Vec<ProverInput<F, HashSigAggAir<T>>>
+    where
+        T: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>> + Send,
+    {
+        self.prover_inputs_with_traces(
+            extra_capacity_bits,
+            &HashSigAggInteraction::default(),
+            BTreeMap::new(),
+            &BTreeMap::new(),
+        )
+    }
+
+    /// Returns prover inputs like [`Self::prover_inputs`], but takes trace of AIR from `traces` by
+    /// name instead of generating it, where `interaction` is expected to be already collected by
+    /// generation of `traces`, and takes public values of AIR from `public_values` by name if
+    /// present.
+    pub(crate) fn prover_inputs_with_traces(
+        &self,
+        extra_capacity_bits: usize,
+        interaction: &HashSigAggInteraction,
+        mut traces: BTreeMap<&'static str, RowMajorMatrix<F>>,
+        public_values: &BTreeMap<&'static str, Vec<F>>,
+    ) -> Vec<ProverInput<F, HashSigAggAir<T>>>
+    where
+        T: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>> + Send,
+    {
+        let registered_traces = self
+            .instances
+            .iter()
+            .map(|instance| traces.remove(instance.descriptor().name))
+            .collect::<Vec<_>>();
+        assert!(
+            traces.is_empty(),
+            "traces of unregistered AIRs: {:?}",
+            traces.keys()
+        );
+        let prover_input = |instance: &DynAirInstance<'a, T>, trace: Option<RowMajorMatrix<F>>| {
+            ProverInput::new(
+                instance.air(),
+                public_values
+                    .get(instance.descriptor().name)
+                    .cloned()
+                    .unwrap_or_else(|| instance.public_values()),
+                trace.unwrap_or_else(|| instance.generate_trace(extra_capacity_bits, interaction)),
+            )
+        };
+        let mut prover_inputs = self
+            .instances
+            .par_iter()
+            .zip(registered_traces)
+            .map(|(instance, trace)| {
+                (trace.is_some() || !instance.descriptor().consumes_interaction)
+                    .then(|| prover_input(&**instance, trace))
+            })
+            .collect::<Vec<_>>();
+        prover_inputs
+            .iter_mut()
+            .zip(&self.instances)
+            .filter(|(prover_input, _)| prover_input.is_none())
+            .for_each(|(input, instance)| *input = Some(prover_input(&**instance, None)));
+        prover_inputs.into_iter().map(Option::unwrap).collect()
+    }
+
+    pub fn verifier_inputs(&self) -> Vec<VerifierInput<F, HashSigAggAir<T>>>
+    where
+        T: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>>,
+    {
+        self.instances
+            .iter()
+            .map(|instance| VerifierInput::new(instance.air(), instance.public_values()))
+            .collect()
+    }
+}
+
+struct BuiltinAirInstance<I, T>(I, PhantomData<fn() -> T>);
+
+impl<I, T> AirInstance<F> for BuiltinAirInstance<I, T>
+where
+    I: AirInstance<F, Air = HashSigAggAir, Interaction = HashSigAggInteraction>,
+{
+    type Air = HashSigAggAir<T>;
+    type Interaction = HashSigAggInteraction;
+
+    fn descriptor(&self) -> AirDescriptor {
+        self.0.descriptor()
+    }
+
+    fn air(&self) -> HashSigAggAir<T> {
+        self.0.air().into_extended()
+    }
+
+    fn public_values(&self) -> Vec<F> {
+        self.0.public_values()
+    }
+
+    fn generate_trace(
+        &self,
+        extra_capacity_bits: usize,
+        interaction: &Self::Interaction,
+    ) -> RowMajorMatrix<F> {
+        self.0.generate_trace(extra_capacity_bits, interaction)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        air::{
+            AirDescriptor, AirInstance, AirRegistry, BusInteractionBuilder, ExtensionAir,
+            HashSigAggAir, HashSigAggInteraction, RangeCheckMessage,
+            generate_prover_inputs_from_pairs,
+        },
+        hash_sig::{F, VerificationTrace, encode_msg},
+        util::zip,
+    };
+    use hash_sig_testdata::mock_vi;
+    use p3_air::{Air, BaseAir, BaseAirWithPublicValues};
+    use p3_air_ext::{InteractionBuilder, check_constraints};
+    use p3_field::PrimeCharacteristicRing;
+    use p3_matrix::{Matrix, dense::RowMajorMatrix};
+
+    #[derive(Clone, Copy, Debug)]
+    struct LimbAir;
+
+    impl ExtensionAir for LimbAir {
+        fn name(&self) -> &'static str {
+            "Limb"
+        }
+    }
+
+    impl BaseAir<F> for LimbAir {
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    impl BaseAirWithPublicValues<F> for LimbAir {}
+
+    impl<AB: InteractionBuilder<F = F>> Air<AB> for LimbAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0).unwrap();
+            builder.send(RangeCheckMessage { value: local[0] }, AB::Expr::ONE);
+        }
+    }
+
+    struct LimbAirInstance(Vec<u32>);
+
+    impl AirInstance<F> for LimbAirInstance {
+        type Air = HashSigAggAir<LimbAir>;
+        type Interaction = HashSigAggInteraction;
+
+        fn descriptor(&self) -> AirDescriptor {
+            AirDescriptor {
+                name: "Limb",
+                consumes_interaction: false,
+            }
+        }
+
+        fn air(&self) -> HashSigAggAir<LimbAir> {
+            HashSigAggAir::Extension(LimbAir)
+        }
+
+        fn generate_trace(&self, _: usize, interaction: &Self::Interaction) -> RowMajorMatrix<F> {
+            self.0
+                .iter()
+                .for_each(|limb| interaction.range_check.send(*limb as usize));
+            RowMajorMatrix::new(self.0.iter().copied().map(F::from_u32).collect(), 1)
+        }
+    }
+
+    #[test]
+    fn extension() {
+        let vi = mock_vi(4);
+        let encoded_msg = encode_msg(vi.msg);
+        let traces = vi
+            .pairs
+            .into_iter()
+            .map(|(pk, sig)| VerificationTrace::generate(vi.epoch, encoded_msg, pk, sig))
+            .collect::<Vec<_>>();
+        let mut registry = AirRegistry::builtin(vi.epoch, encoded_msg, &traces);
+        registry.register(LimbAirInstance(vec![0, 1, 4095, 1]));
+        check_constraints(&registry.prover_inputs(0));
+    }
+
+    #[test]
+    fn extension_from_pairs() {
+        let vi = mock_vi(4);
+        let encoded_msg = encode_msg(vi.msg);
+        let mut registry = AirRegistry::builtin(vi.epoch, encoded_msg, &[]);
+        registry.register(LimbAirInstance(vec![0, 1, 4095, 1]));
+        let prover_inputs = generate_prover_inputs_from_pairs(
+            &registry,
+            0,
+            vi.epoch,
+            vi.msg,
+            vi.pairs.len(),
+            vi.pairs,
+            size_of::<VerificationTrace>(),
+        )
+        .unwrap();
+        assert!(
+            zip!(&prover_inputs, registry.descriptors())
+                .all(|(prover_input, descriptor)| prover_input.air().name() == descriptor.name)
+        );
+        assert!(matches!(
+            prover_inputs.last().unwrap().air(),
+            HashSigAggAir::Extension(LimbAir)
+        ));
+        check_constraints(&prover_inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "AIR Limb already registered")]
+    fn duplicated_extension() {
+        let mut registry = AirRegistry::<LimbAir>::default();
+        registry
+            .register(LimbAirInstance(vec![0]))
+            .register(LimbAirInstance(vec![1]));
+    }
+}
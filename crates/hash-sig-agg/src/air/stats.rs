@@ -0,0 +1,83 @@
+use crate::{
+    air::{
+        HashSigAggAir, chain, decomposition, main, merkle_tree, range_check,
+        symbolic::symbolic_constraints, verifier_inputs,
+    },
+    hash_sig::{F, HASH_FE_LEN, MSG_LEN},
+};
+use p3_air::BaseAir;
+use p3_field::PrimeCharacteristicRing;
+
+/// Statistics of an AIR for aggregation of a given amount of signatures.
+#[derive(Clone, Debug)]
+pub struct AirStats {
+    pub name: &'static str,
+    pub width: usize,
+    pub height: usize,
+    pub num_constraints: usize,
+    pub max_constraint_degree: usize,
+    pub num_interactions: usize,
+}
+
+impl AirStats {
+    /// Returns amount of cells of main trace.
+    pub const fn cells(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+const fn trace_height(air: &HashSigAggAir, num_sigs: usize) -> usize {
+    match air {
+        HashSigAggAir::Chain(_) => chain::generation::trace_height(num_sigs),
+        HashSigAggAir::Decomposition(_) => decomposition::generation::trace_height(num_sigs),
+        HashSigAggAir::Main(_) => main::generation::trace_height(num_sigs),
+        HashSigAggAir::MerkleTree(_) => merkle_tree::generation::trace_height(num_sigs),
+        HashSigAggAir::RangeCheck(_) => range_check::generation::trace_height(),
+        HashSigAggAir::Extension(air) => match *air {},
+    }
+}
+
+/// Returns statistics of builtin AIRs for aggregation of `num_sigs` signatures, in the same
+/// order as [`verifier_inputs`].
+pub fn air_stats(num_sigs: usize) -> Vec<AirStats> {
+    verifier_inputs(0, [0; MSG_LEN], [F::ZERO; HASH_FE_LEN])
+        .iter()
+        .map(|input| {
+            let air = input.air();
+            let symbolic = symbolic_constraints(air);
+            AirStats {
+                name: air.name(),
+                width: air.width(),
+                height: trace_height(air, num_sigs),
+                num_constraints: symbolic.constraints.len(),
+                max_constraint_degree: symbolic.max_constraint_degree(),
+                num_interactions: symbolic.interactions.len(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::air::{MAX_CONSTRAINT_DEGREE, generate_prover_inputs, stats::air_stats};
+    use hash_sig_testdata::mock_vi;
+    use p3_matrix::Matrix;
+
+    #[test]
+    fn stats() {
+        let stats = air_stats(12);
+        let prover_inputs = generate_prover_inputs(0, mock_vi(12));
+        assert_eq!(stats.len(), prover_inputs.len());
+        for (stats, prover_input) in stats.iter().zip(&prover_inputs) {
+            assert_eq!(stats.name, prover_input.air().name());
+            assert_eq!(stats.width, prover_input.trace().width());
+            assert_eq!(stats.height, prover_input.trace().height());
+            assert!(stats.max_constraint_degree <= MAX_CONSTRAINT_DEGREE);
+        }
+        assert_eq!(
+            stats.iter().map(|stats| stats.max_constraint_degree).max(),
+            Some(MAX_CONSTRAINT_DEGREE)
+        );
+        assert!(stats.iter().all(|stats| stats.num_interactions > 0));
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{
+    air::{Bus, ExtensionAir, HashSigAggAir},
+    hash_sig::F,
+};
+use core::fmt::Write as _;
+use itertools::Itertools;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
+use p3_air_ext::{Entry, InteractionBuilder, SymbolicExpression, SymbolicVariable};
+use p3_matrix::dense::RowMajorMatrix;
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+/// Interaction pushed with symbolic fields and signed count (negated for receive).
+#[derive(Clone, Debug)]
+pub struct SymbolicInteraction {
+    pub bus_index: usize,
+    pub fields: Vec<SymbolicExpression<F>>,
+    pub count: SymbolicExpression<F>,
+}
+
+/// Constraints and interactions of an AIR in order of evaluation.
+#[derive(Clone, Debug)]
+pub struct SymbolicConstraints {
+    pub constraints: Vec<SymbolicExpression<F>>,
+    pub interactions: Vec<SymbolicInteraction>,
+}
+
+impl SymbolicConstraints {
+    pub fn max_constraint_degree(&self) -> usize {
+        self.constraints
+            .iter()
+            .map(SymbolicExpression::degree_multiple)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Evaluates `air` symbolically over the window of local and next row.
+pub fn symbolic_constraints<T: BaseAirWithPublicValues<F>>(
+    air: &HashSigAggAir<T>,
+) -> SymbolicConstraints
+where
+    HashSigAggAir<T>: Air<SymbolicRecorder>,
+{
+    let width = air.width();
+    let mut builder = SymbolicRecorder {
+        main: RowMajorMatrix::new(
+            [0, 1]
+                .into_iter()
+                .flat_map(|offset| {
+                    (0..width).map(move |idx| SymbolicVariable::new(Entry::Main { offset }, idx))
+                })
+                .collect(),
+            width,
+        ),
+        public_values: (0..air.num_public_values())
+            .map(|idx| SymbolicVariable::new(Entry::Public, idx))
+            .collect(),
+        constraints: SymbolicConstraints {
+            constraints: Vec::new(),
+            interactions: Vec::new(),
+        },
+    };
+    air.eval(&mut builder);
+    builder.constraints
+}
+
+/// Writes constraints and interactions of `airs` as JSON, one expression per line so changes of
+/// the constraint system are easy to diff.
+///
+/// Expressions are trees of `{"op": "add" | "sub" | "mul" | "neg", "args": [..]}` with leaves
+/// `{"col": "local.<name>" | "next.<name>"}`, `{"public": <index>}`, `{"const": <value>}` and
+/// `{"selector": "is_first_row" | "is_last_row" | "is_transition"}`, where `<name>` is from
+/// [`HashSigAggAir::column_names`]. Interaction counts are negated for receive.
+pub fn write_constraints_json<T: BaseAirWithPublicValues<F> + ExtensionAir>(
+    mut writer: impl Write,
+    airs: &[HashSigAggAir<T>],
+) -> io::Result<()>
+where
+    HashSigAggAir<T>: Air<SymbolicRecorder>,
+{
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"airs\": [")?;
+    for (idx, air) in airs.iter().enumerate() {
+        let names = air.column_names();
+        let symbolic = symbolic_constraints(air);
+        let expr = |expr: &SymbolicExpression<F>| {
+            let mut json = String::new();
+            write_expr_json(&mut json, expr, &names);
+            json
+        };
+        writeln!(writer, "    {{")?;
+        writeln!(writer, "      \"name\": \"{}\",", air.name())?;
+        writeln!(writer, "      \"width\": {},", air.width())?;
+        writeln!(
+            writer,
+            "      \"num_public_values\": {},",
+            air.num_public_values()
+        )?;
+        writeln!(writer, "      \"constraints\": [")?;
+        let constraints = symbolic.constraints.iter().map(|constraint| {
+            format!(
+                "        {{\"degree\": {}, \"expr\": {}}}",
+                constraint.degree_multiple(),
+                expr(constraint)
+            )
+        });
+        writeln!(writer, "{}", constraints.format(",\n"))?;
+        writeln!(writer, "      ],")?;
+        writeln!(writer, "      \"interactions\": [")?;
+        let interactions = symbolic.interactions.iter().map(|interaction| {
+            let bus = Bus::from_index(interaction.bus_index).map_or_else(
+                || interaction.bus_index.to_string(),
+                |bus| format!("{bus:?}"),
+            );
+            format!(
+                "        {{\"bus\": \"{bus}\", \"fields\": [{}], \"count\": {}}}",
+                interaction.fields.iter().map(expr).format(", "),
+                expr(&interaction.count)
+            )
+        });
+        writeln!(writer, "{}", interactions.format(",\n"))?;
+        writeln!(writer, "      ]")?;
+        let sep = if idx + 1 == airs.len() { "" } else { "," };
+        writeln!(writer, "    }}{sep}")?;
+    }
+    writeln!(writer, "  ]")?;
+    writeln!(writer, "}}")?;
+    writer.flush()
+}
+
+fn write_expr_json(json: &mut String, expr: &SymbolicExpression<F>, names: &[String]) {
+    match expr {
+        SymbolicExpression::Variable(var) => match var.entry {
+            Entry::Main { offset: 0 } => {
+                write!(json, "{{\"col\": \"local.{}\"}}", names[var.index])
+            }
+            Entry::Main { offset: 1 } => write!(json, "{{\"col\": \"next.{}\"}}", names[var.index]),
+            Entry::Public => write!(json, "{{\"public\": {}}}", var.index),
+            entry => write!(json, "{{\"var\": \"{entry:?}[{}]\"}}", var.index),
+        }
+        .unwrap(),
+        SymbolicExpression::IsFirstRow => json.push_str("{\"selector\": \"is_first_row\"}"),
+        SymbolicExpression::IsLastRow => json.push_str("{\"selector\": \"is_last_row\"}"),
+        SymbolicExpression::IsTransition => json.push_str("{\"selector\": \"is_transition\"}"),
+        SymbolicExpression::Constant(value) => write!(json, "{{\"const\": {value}}}").unwrap(),
+        SymbolicExpression::Add { x, y, .. } => write_op_json(json, "add", &[x, y], names),
+        SymbolicExpression::Sub { x, y, .. } => write_op_json(json, "sub", &[x, y], names),
+        SymbolicExpression::Mul { x, y, .. } => write_op_json(json, "mul", &[x, y], names),
+        SymbolicExpression::Neg { x, .. } => write_op_json(json, "neg", &[x], names),
+    }
+}
+
+fn write_op_json(
+    json: &mut String,
+    op: &str,
+    args: &[&Arc<SymbolicExpression<F>>],
+    names: &[String],
+) {
+    write!(json, "{{\"op\": \"{op}\", \"args\": [").unwrap();
+    for (idx, arg) in args.iter().enumerate() {
+        if idx > 0 {
+            json.push_str(", ");
+        }
+        write_expr_json(json, arg, names);
+    }
+    json.push_str("]}");
+}
+
+/// Records constraints and interactions as symbolic expressions.
+pub struct SymbolicRecorder {
+    main: RowMajorMatrix<SymbolicVariable<F>>,
+    public_values: Vec<SymbolicVariable<F>>,
+    constraints: SymbolicConstraints,
+}
+
+impl SymbolicRecorder {
+    fn push(
+        &mut self,
+        bus_index: usize,
+        fields: impl IntoIterator<Item: Into<SymbolicExpression<F>>>,
+        count: SymbolicExpression<F>,
+    ) {
+        self.constraints.interactions.push(SymbolicInteraction {
+            bus_index,
+            fields: fields.into_iter().map(Into::into).collect(),
+            count,
+        });
+    }
+}
+
+impl AirBuilder for SymbolicRecorder {
+    type Expr = SymbolicExpression<F>;
+    type Var = SymbolicVariable<F>;
+    type M = RowMajorMatrix<SymbolicVariable<F>>;
+    type F = F;
+
+    fn main(&self) -> Self::M {
+        self.main.clone()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        SymbolicExpression::IsFirstRow
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        SymbolicExpression::IsLastRow
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        assert_eq!(size, 2, "only window size 2 is supported");
+        SymbolicExpression::IsTransition
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.constraints.constraints.push(x.into());
+    }
+}
+
+impl AirBuilderWithPublicValues for SymbolicRecorder {
+    type PublicVar = SymbolicVariable<F>;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        &self.public_values
+    }
+}
+
+impl InteractionBuilder for SymbolicRecorder {
+    const ONLY_INTERACTION: bool = false;
+
+    fn push_interaction(
+        &mut self,
+        bus_index: usize,
+        fields: impl IntoIterator<Item: Into<Self::Expr>>,
+        count: impl Into<Self::Expr>,
+        _: u32,
+    ) {
+        self.push(bus_index, fields, count.into());
+    }
+
+    fn push_send(
+        &mut self,
+        bus_index: usize,
+        fields: impl IntoIterator<Item: Into<Self::Expr>>,
+        count: impl Into<Self::Expr>,
+    ) {
+        self.push(bus_index, fields, count.into());
+    }
+
+    fn push_receive(
+        &mut self,
+        bus_index: usize,
+        fields: impl IntoIterator<Item: Into<Self::Expr>>,
+        count: impl Into<Self::Expr>,
+    ) {
+        self.push(bus_index, fields, -count.into());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        air::{symbolic::write_constraints_json, verifier_inputs},
+        hash_sig::{F, HASH_FE_LEN, MSG_LEN},
+    };
+    use p3_field::PrimeCharacteristicRing;
+    use std::{env, fs};
+
+    const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/constraints.json");
+
+    /// Fails when the constraint system changes, which is expected to be reviewed and accepted by
+    /// running with `UPDATE_GOLDEN=1`.
+    #[test]
+    fn golden() {
+        let airs = verifier_inputs(0, [0; MSG_LEN], [F::ZERO; HASH_FE_LEN])
+            .iter()
+            .map(|input| input.air().clone())
+            .collect::<Vec<_>>();
+        let mut json = Vec::new();
+        write_constraints_json(&mut json, &airs).unwrap();
+        let json = String::from_utf8(json).unwrap();
+
+        if env::var("UPDATE_GOLDEN").as_deref() == Ok("1") {
+            fs::create_dir_all(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata")).unwrap();
+            fs::write(GOLDEN, json).unwrap();
+            return;
+        }
+        let golden = fs::read_to_string(GOLDEN).unwrap_or_else(|err| {
+            panic!("failed to read {GOLDEN}: {err}, run with UPDATE_GOLDEN=1 to generate")
+        });
+        assert!(
+            golden == json,
+            "constraint system differs from {GOLDEN}, rerun with UPDATE_GOLDEN=1 to accept"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Negative tests asserting tampered traces are rejected by constraints or bus balance.
+
+use crate::{
+    air::{
+        HashSigAggAir,
+        chain::{ChainCols, NUM_CHAIN_COLS},
+        debug::{failed_constraints, unbalanced_interactions},
+        decomposition::{DecompositionCols, NUM_DECOMPOSITION_COLS},
+        generate_prover_inputs, generate_prover_inputs_from_traces,
+        main::main_public_values,
+        merkle_tree::{MerkleTreeCols, NUM_MERKLE_TREE_COLS},
+        range_check::{NUM_RANGE_CHECK_COLS, RangeCheckCols},
+    },
+    hash_sig::{
+        F, MSG_LEN, NUM_CHUNKS, SPONGE_PERM, VerificationTrace, encode_msg, signer_commitment,
+    },
+};
+use core::borrow::BorrowMut;
+use hash_sig_testdata::{
+    fault::{Fault, inject_fault},
+    mock_vi_with_seed,
+};
+use p3_air_ext::ProverInput;
+use p3_field::PrimeCharacteristicRing;
+use rand::{SeedableRng, rngs::StdRng};
+
+const NUM_SIGS: usize = 4;
+const SEED: u64 = 0;
+
+fn honest_traces() -> (u32, [u8; MSG_LEN], Vec<VerificationTrace>) {
+    let vi = mock_vi_with_seed(NUM_SIGS, SEED);
+    let encoded_msg = encode_msg(vi.msg);
+    let traces = vi
+        .pairs
+        .into_iter()
+        .map(|(pk, sig)| VerificationTrace::generate(vi.epoch, encoded_msg, pk, sig))
+        .collect();
+    (vi.epoch, vi.msg, traces)
+}
+
+fn prover_inputs(
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    traces: &[VerificationTrace],
+) -> Vec<ProverInput<F, HashSigAggAir>> {
+    generate_prover_inputs_from_traces(0, epoch, encode_msg(msg), traces)
+}
+
+fn assert_rejected(prover_inputs: &[ProverInput<F, HashSigAggAir>], case: &str) {
+    assert!(
+        !failed_constraints(prover_inputs).is_empty()
+            || !unbalanced_interactions(prover_inputs).is_empty(),
+        "tampered {case} is accepted"
+    );
+}
+
+/// Mutates row `row` of trace of `air` in honest prover inputs, and asserts it's rejected.
+fn assert_rejected_when<C>(air: &str, width: usize, row: usize, tamper: impl FnOnce(&mut C))
+where
+    [F]: BorrowMut<C>,
+{
+    let (epoch, msg, traces) = honest_traces();
+    let mut prover_inputs = prover_inputs(epoch, msg, &traces);
+    let prover_input = prover_inputs
+        .iter_mut()
+        .find(|prover_input| prover_input.air().name() == air)
+        .unwrap();
+    let mut trace = prover_input.trace().clone();
+    tamper(trace.values[row * width..(row + 1) * width].borrow_mut());
+    *prover_input = ProverInput::new(
+        prover_input.air().clone(),
+        prover_input.public_values().to_vec(),
+        trace,
+    );
+    assert_rejected(&prover_inputs, &format!("{air} row {row}"));
+}
+
+#[test]
+fn honest() {
+    let (epoch, msg, traces) = honest_traces();
+    let prover_inputs = prover_inputs(epoch, msg, &traces);
+    assert!(failed_constraints(&prover_inputs).is_empty());
+    assert!(unbalanced_interactions(&prover_inputs).is_empty());
+}
+
+#[test]
+fn chain_is_x_i() {
+    assert_rejected_when("Chain", NUM_CHAIN_COLS, 0, |cols: &mut ChainCols<F>| {
+        cols.is_x_i = F::ONE - cols.is_x_i;
+    });
+}
+
+#[test]
+fn chain_step_bits() {
+    assert_rejected_when("Chain", NUM_CHAIN_COLS, 1, |cols: &mut ChainCols<F>| {
+        cols.chain_step_bits[0] = F::ONE - cols.chain_step_bits[0];
+    });
+}
+
+#[test]
+fn merkle_tree_level() {
+    assert_rejected_when(
+        "MerkleTree",
+        NUM_MERKLE_TREE_COLS,
+        SPONGE_PERM,
+        |cols: &mut MerkleTreeCols<F>| cols.level.step += F::ONE,
+    );
+}
+
+#[test]
+fn merkle_tree_is_right() {
+    assert_rejected_when(
+        "MerkleTree",
+        NUM_MERKLE_TREE_COLS,
+        SPONGE_PERM,
+        |cols: &mut MerkleTreeCols<F>| cols.is_right = F::ONE - cols.is_right,
+    );
+}
+
+#[test]
+fn merkle_tree_epoch_dec() {
+    assert_rejected_when(
+        "MerkleTree",
+        NUM_MERKLE_TREE_COLS,
+        SPONGE_PERM,
+        |cols: &mut MerkleTreeCols<F>| cols.epoch_dec += F::ONE,
+    );
+}
+
+#[test]
+fn decomposition_limbs() {
+    assert_rejected_when(
+        "Decomposition",
+        NUM_DECOMPOSITION_COLS,
+        0,
+        |cols: &mut DecompositionCols<F>| cols.value_ls_limbs[0] += F::ONE,
+    );
+    assert_rejected_when(
+        "Decomposition",
+        NUM_DECOMPOSITION_COLS,
+        0,
+        |cols: &mut DecompositionCols<F>| cols.acc_limbs[0] += F::ONE,
+    );
+}
+
+#[test]
+fn range_check_mult() {
+    assert_rejected_when(
+        "RangeCheck",
+        NUM_RANGE_CHECK_COLS,
+        0,
+        |cols: &mut RangeCheckCols<F>| cols.mult += F::ONE,
+    );
+}
+
+#[test]
+fn wrong_target_sum() {
+    assert_rejected_when(
+        "Decomposition",
+        NUM_DECOMPOSITION_COLS,
+        crate::air::decomposition::generation::NUM_ROWS_PER_SIG - 1,
+        |cols: &mut DecompositionCols<F>| cols.sum += F::ONE,
+    );
+}
+
+#[test]
+fn swapped_merkle_siblings() {
+    let (epoch, msg, mut traces) = honest_traces();
+    traces[0].sig.merkle_siblings.swap(0, 1);
+    assert_rejected(&prover_inputs(epoch, msg, &traces), "Merkle siblings");
+}
+
+#[test]
+fn wrong_epoch_bits() {
+    let (epoch, msg, traces) = honest_traces();
+    assert_rejected(&prover_inputs(epoch ^ 1, msg, &traces), "epoch");
+}
+
+#[test]
+fn wrong_signer_commitment() {
+    let (epoch, msg, traces) = honest_traces();
+    let mut prover_inputs = prover_inputs(epoch, msg, &traces);
+    let main = prover_inputs
+        .iter_mut()
+        .find(|prover_input| prover_input.air().name() == "Main")
+        .unwrap();
+    let signer_commitment = signer_commitment(traces[1..].iter().map(|trace| &trace.pk));
+    *main = ProverInput::new(
+        main.air().clone(),
+        main_public_values(signer_commitment),
+        main.trace().clone(),
+    );
+    assert_rejected(&prover_inputs, "signer commitment");
+}
+
+#[test]
+fn labeled_faults() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let vi = mock_vi_with_seed(NUM_SIGS, SEED);
+    for fault in [
+        Fault::WrongTargetSum,
+        Fault::CorruptedChain(NUM_CHUNKS - 1),
+        Fault::WrongSibling(0),
+        Fault::MismatchedParameter,
+        Fault::WrongEpoch(vi.epoch ^ 1),
+        Fault::DuplicatedSigner(1),
+    ] {
+        let mut vi = vi.clone();
+        inject_fault(&mut vi, 0, fault, &mut rng);
+        let prover_inputs = generate_prover_inputs(0, vi);
+        let rejected = !failed_constraints(&prover_inputs).is_empty()
+            || !unbalanced_interactions(&prover_inputs).is_empty();
+        assert_eq!(
+            rejected,
+            fault.expected_error().is_some(),
+            "unexpected AIR result of fault {fault:?}"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    air::{HashSigAggAir, generate_prover_inputs, verifier_inputs},
+    hash_sig::{
+        F, HASH_FE_LEN, MSG_LEN, PARAM_FE_LEN, PublicKey, VerificationInput, signer_commitment,
+    },
+};
+use core::fmt::{self, Debug, Display};
+use p3_air_ext::{ProverInput, VerifierInput};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Aggregate proofs over disjoint signer shards of the same epoch and message, where each proof is
+/// bound to signers of its manifest by the signer commitment in public values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bundle<P> {
+    pub epoch: u32,
+    pub msg: [u8; MSG_LEN],
+    pub manifest: Vec<ShardManifest>,
+    pub proofs: Vec<P>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShardManifest {
+    pub signers: Vec<PublicKey>,
+}
+
+#[derive(Debug)]
+pub enum BundleError<E> {
+    UnmatchedShardCount,
+    DuplicatedSigner { shard: usize, signer: usize },
+    InvalidProof { shard: usize, err: E },
+}
+
+impl<E: Debug> Display for BundleError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnmatchedShardCount => write!(f, "unmatched amount of manifest and proofs"),
+            Self::DuplicatedSigner { shard, signer } => {
+                write!(f, "duplicated signer {signer} of shard {shard}")
+            }
+            Self::InvalidProof { shard, err } => {
+                write!(f, "invalid proof of shard {shard}: {err:?}")
+            }
+        }
+    }
+}
+
+/// Splits `vi` into at most `num_shards` verification inputs over disjoint signer shards.
+pub fn split_shards(vi: VerificationInput, num_shards: usize) -> Vec<VerificationInput> {
+    let shard_size = vi.pairs.len().div_ceil(num_shards.max(1)).max(1);
+    vi.pairs
+        .chunks(shard_size)
+        .map(|pairs| VerificationInput {
+            epoch: vi.epoch,
+            msg: vi.msg,
+            pairs: pairs.to_vec(),
+        })
+        .collect()
+}
+
+impl<P> Bundle<P> {
+    /// Proves each of `shards` with `prove`, which takes the prover inputs generated with
+    /// `extra_capacity_bits`.
+    pub fn prove(
+        extra_capacity_bits: usize,
+        shards: Vec<VerificationInput>,
+        mut prove: impl FnMut(Vec<ProverInput<F, HashSigAggAir>>) -> P,
+    ) -> Self {
+        assert!(!shards.is_empty(), "bundle requires at least one shard");
+        let (epoch, msg) = (shards[0].epoch, shards[0].msg);
+        let (manifest, proofs) = shards
+            .into_iter()
+            .map(|vi| {
+                assert_eq!((vi.epoch, vi.msg), (epoch, msg));
+                let signers = vi.pairs.iter().map(|(pk, _)| *pk).collect();
+                let proof = prove(generate_prover_inputs(extra_capacity_bits, vi));
+                (ShardManifest { signers }, proof)
+            })
+            .unzip();
+        Self {
+            epoch,
+            msg,
+            manifest,
+            proofs,
+        }
+    }
+
+    /// Checks that signers are disjoint across shards, then verifies each proof with `verify`,
+    /// which takes the verifier inputs bound to signers of the shard.
+    pub fn verify<E>(
+        &self,
+        mut verify: impl FnMut(Vec<VerifierInput<F, HashSigAggAir>>, &P) -> Result<(), E>,
+    ) -> Result<(), BundleError<E>> {
+        if self.manifest.len() != self.proofs.len() {
+            return Err(BundleError::UnmatchedShardCount);
+        }
+        let mut signers = HashSet::<([F; PARAM_FE_LEN], [F; HASH_FE_LEN])>::new();
+        for (shard, (manifest, proof)) in self.manifest.iter().zip(&self.proofs).enumerate() {
+            for (signer, pk) in manifest.signers.iter().enumerate() {
+                if !signers.insert((pk.parameter, pk.merkle_root)) {
+                    return Err(BundleError::DuplicatedSigner { shard, signer });
+                }
+            }
+            let signer_commitment = signer_commitment(&manifest.signers);
+            verify(
+                verifier_inputs(self.epoch, self.msg, signer_commitment),
+                proof,
+            )
+            .map_err(|err| BundleError::InvalidProof { shard, err })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bundle::{Bundle, BundleError, ShardManifest, split_shards},
+        hash_sig::VerificationInput,
+    };
+    use hash_sig_testdata::mock_vi;
+
+    #[test]
+    fn split() {
+        let vi: VerificationInput = mock_vi(10);
+        let shards = split_shards(vi.clone(), 3);
+        assert_eq!(
+            shards.iter().map(|vi| vi.pairs.len()).collect::<Vec<_>>(),
+            [4, 4, 2]
+        );
+        assert!(
+            shards
+                .iter()
+                .flat_map(|vi| &vi.pairs)
+                .zip(&vi.pairs)
+                .all(|(a, b)| a.0.merkle_root == b.0.merkle_root)
+        );
+
+        let empty = VerificationInput {
+            pairs: Vec::new(),
+            ..vi
+        };
+        assert!(split_shards(empty.clone(), 3).is_empty());
+        assert!(split_shards(empty, 0).is_empty());
+    }
+
+    #[test]
+    fn duplicated_signer() {
+        let vi: VerificationInput = mock_vi(4);
+        let manifest = |range: core::ops::Range<usize>| ShardManifest {
+            signers: vi.pairs[range].iter().map(|(pk, _)| *pk).collect(),
+        };
+        let mut bundle = Bundle {
+            epoch: vi.epoch,
+            msg: vi.msg,
+            manifest: vec![manifest(0..2), manifest(2..4)],
+            proofs: vec![(), ()],
+        };
+        assert!(bundle.verify(|_, ()| Ok::<_, ()>(())).is_ok());
+
+        bundle.manifest[1] = manifest(1..3);
+        assert!(matches!(
+            bundle.verify(|_, ()| Ok::<_, ()>(())),
+            Err(BundleError::DuplicatedSigner {
+                shard: 1,
+                signer: 0
+            })
+        ));
+
+        bundle.proofs.pop();
+        assert!(matches!(
+            bundle.verify(|_, ()| Ok::<_, ()>(())),
+            Err(BundleError::UnmatchedShardCount)
+        ));
+    }
+}
@@ -13,6 +13,8 @@ pub struct CycleBits<T, const N: usize> {
     pub bits: [T; N],
 }
 
+crate::util::column::impl_column_names!(CycleBits<const N: usize> { bits });
+
 impl<T, const N: usize> Deref for CycleBits<T, N> {
     type Target = [T; N];
 
@@ -13,6 +13,8 @@ pub struct CycleInt<T, const N: usize> {
     pub is_last_step: IsEqualCols<T>,
 }
 
+crate::util::column::impl_column_names!(CycleInt<const N: usize> { step, is_last_step });
+
 impl<T, const N: usize> Deref for CycleInt<T, N> {
     type Target = T;
 
@@ -1,4 +1,7 @@
-use crate::gadget::is_zero::IsZeroCols;
+use crate::{
+    gadget::is_zero::IsZeroCols,
+    util::column::{Col, ColumnNames},
+};
 use core::mem::MaybeUninit;
 use core::ops::Deref;
 use p3_air::AirBuilder;
@@ -8,6 +11,12 @@ use p3_field::Field;
 #[derive(Copy, Clone, Debug)]
 pub struct IsEqualCols<T>(pub IsZeroCols<T>);
 
+impl ColumnNames for IsEqualCols<Col> {
+    fn push_column_names(prefix: &str, names: &mut Vec<String>) {
+        IsZeroCols::push_column_names(prefix, names);
+    }
+}
+
 impl<T> Deref for IsEqualCols<T> {
     type Target = IsZeroCols<T>;
 
@@ -9,6 +9,8 @@ pub struct IsZeroCols<T> {
     pub output: T,
 }
 
+crate::util::column::impl_column_names!(IsZeroCols { inv, output });
+
 impl<T: Field> IsZeroCols<MaybeUninit<T>> {
     #[inline]
     pub fn populate(&mut self, input: T) {
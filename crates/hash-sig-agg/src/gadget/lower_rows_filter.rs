@@ -9,6 +9,8 @@ pub struct LowerRowsFilterCols<T> {
     pub is_active: T,
 }
 
+crate::util::column::impl_column_names!(LowerRowsFilterCols { is_active });
+
 impl<T> Deref for LowerRowsFilterCols<T> {
     type Target = T;
 
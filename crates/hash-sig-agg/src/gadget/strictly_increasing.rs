@@ -12,6 +12,12 @@ pub struct StrictlyIncreasingCols<T, const MAX_DIFF_BITS: usize> {
     pub diff_inv: T,
 }
 
+crate::util::column::impl_column_names!(StrictlyIncreasingCols<const MAX_DIFF_BITS: usize> {
+    value,
+    diff_bits,
+    diff_inv,
+});
+
 impl<T, const MAX_DIFF_BITS: usize> Deref for StrictlyIncreasingCols<T, MAX_DIFF_BITS> {
     type Target = T;
 
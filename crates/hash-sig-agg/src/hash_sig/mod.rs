@@ -1,4 +1,4 @@
-use core::array::from_fn;
+use core::{array::from_fn, iter::zip};
 use hash_sig_verifier::{
     concat_array,
     instantiation::{
@@ -6,7 +6,7 @@ use hash_sig_verifier::{
         poseidon2::{Poseidon2Parameter as _, msg_hash_to_chunks},
     },
 };
-use p3_field::PrimeField32;
+use p3_field::{PrimeCharacteristicRing, PrimeField32};
 use p3_maybe_rayon::prelude::*;
 
 mod param;
@@ -33,6 +33,9 @@ pub const MODULUS: u32 = F::ORDER_U32;
 
 pub const SPONGE_CAPACITY_VALUES: [F; SPONGE_CAPACITY] = Poseidon2Parameter::CAPACITY_VALUES;
 
+/// Amount of field elements of a signer absorbed by [`signer_commitment`] per permutation.
+pub const SIGNER_FE_LEN: usize = PARAM_FE_LEN + HASH_FE_LEN;
+
 #[derive(Clone, Copy, Debug)]
 pub struct VerificationTrace {
     pub pk: PublicKey,
@@ -44,6 +47,11 @@ pub struct VerificationTrace {
 }
 
 impl VerificationTrace {
+    /// Generates trace of verifying `sig` of `pk`, which doesn't panic even if `sig` is invalid.
+    ///
+    /// When chunks of the message hash don't sum up to [`TARGET_SUM`], chain inputs are truncated
+    /// to or padded with zeros up to [`TARGET_SUM`] steps, and so are the rows of chain AIR, so the
+    /// AIR traces can still be generated but don't satisfy the constraints.
     pub fn generate(
         epoch: u32,
         encoded_msg: [F; MSG_FE_LEN],
@@ -63,9 +71,7 @@ impl VerificationTrace {
             .unzip::<_, _, Vec<_>, Vec<_>>();
         let chain_inputs = {
             let mut iter = chain_inputs.into_iter().flatten();
-            let chain_inputs = from_fn(|_| iter.next().unwrap());
-            debug_assert!(iter.next().is_none());
-            chain_inputs
+            from_fn(|_| iter.next().unwrap_or_default())
         };
         Self {
             pk,
@@ -111,3 +117,20 @@ pub fn chain_and_input(
         )
     })
 }
+
+/// Returns commitment of ordered `signers`, which is a sponge over Poseidon2 of width 24 starting
+/// from zero state, absorbing parameter and Merkle root of one signer per permutation, and
+/// squeezing the first [`HASH_FE_LEN`] elements.
+pub fn signer_commitment<'a>(signers: impl IntoIterator<Item = &'a PublicKey>) -> [F; HASH_FE_LEN] {
+    let state = signers.into_iter().fold([F::ZERO; 24], |state, pk| {
+        Poseidon2Parameter::permutation_t24(signer_commitment_input(state, pk))
+    });
+    from_fn(|i| state[i])
+}
+
+/// Returns input of permutation of [`signer_commitment`] absorbing `pk` into `state`.
+pub fn signer_commitment_input(mut state: [F; 24], pk: &PublicKey) -> [F; 24] {
+    zip(&mut state, pk.parameter.into_iter().chain(pk.merkle_root))
+        .for_each(|(state, value)| *state += value);
+    state
+}
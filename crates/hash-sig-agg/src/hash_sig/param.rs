@@ -19,7 +19,7 @@ pub mod koala_bear {
     pub use p3_poseidon2_util::instantiation::horizon::koala_bear::constant::{
         HALF_FULL_ROUNDS, RC16, RC24, SBOX_DEGREE, partial_round,
     };
-    pub type E = p3_field::extension::BinomialExtensionField<F, 4>; // FIXME: Use higher degree when possible.
+    pub type E = p3_field::extension::BinomialExtensionField<F, 4>; // FIXME: Use degree 8 after bumping Plonky3, see README.
     pub type Poseidon2<const WIDTH: usize> = p3_koala_bear::Poseidon2KoalaBear<WIDTH>;
     pub type Poseidon2LinearLayers<const WIDTH: usize> =
         p3_poseidon2_util::instantiation::horizon::Poseidon2LinearLayersHorizon<F, WIDTH>;
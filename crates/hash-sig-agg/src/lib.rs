@@ -1,4 +1,7 @@
 pub mod air;
+pub mod bundle;
 pub mod gadget;
 pub mod hash_sig;
+pub mod session;
+pub mod ssz;
 pub(crate) mod util;
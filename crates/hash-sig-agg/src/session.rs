@@ -0,0 +1,183 @@
+use crate::{
+    air::{HashSigAggAir, generate_prover_inputs_from_traces},
+    hash_sig::{
+        F, HASH_FE_LEN, MSG_FE_LEN, MSG_LEN, PARAM_FE_LEN, Poseidon2TargetSum, PublicKey,
+        Signature, VerificationTrace, encode_msg,
+    },
+};
+use core::fmt::{self, Display};
+use hash_sig_verifier::instantiation::Instantiation;
+use p3_air_ext::ProverInput;
+use std::collections::{HashMap, hash_map::Entry};
+
+type SignerKey = ([F; PARAM_FE_LEN], [F; HASH_FE_LEN]);
+
+const fn signer_key(pk: &PublicKey) -> SignerKey {
+    (pk.parameter, pk.merkle_root)
+}
+
+/// Aggregation of pairs arriving one at a time for the same epoch and message.
+///
+/// Each pair is verified natively on insertion and its [`VerificationTrace`] is cached, so
+/// [`AggregationSession::finalize`] only generates the AIR traces.
+#[derive(Clone, Debug)]
+pub struct AggregationSession {
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    encoded_msg: [F; MSG_FE_LEN],
+    traces: Vec<VerificationTrace>,
+    indices: HashMap<SignerKey, usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionError {
+    DuplicatedSigner,
+    UnknownSigner,
+    InvalidSignature(String),
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicatedSigner => write!(f, "signer already in session"),
+            Self::UnknownSigner => write!(f, "signer not in session"),
+            Self::InvalidSignature(err) => write!(f, "invalid signature: {err}"),
+        }
+    }
+}
+
+impl AggregationSession {
+    pub fn new(epoch: u32, msg: [u8; MSG_LEN]) -> Self {
+        Self {
+            epoch,
+            msg,
+            encoded_msg: encode_msg(msg),
+            traces: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+
+    pub const fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    pub const fn msg(&self) -> [u8; MSG_LEN] {
+        self.msg
+    }
+
+    pub const fn len(&self) -> usize {
+        self.traces.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.traces.is_empty()
+    }
+
+    pub fn contains(&self, pk: &PublicKey) -> bool {
+        self.indices.contains_key(&signer_key(pk))
+    }
+
+    /// Returns signers in the order committed by public values of [`Self::finalize`], see
+    /// [`signer_commitment`](crate::hash_sig::signer_commitment).
+    pub fn signers(&self) -> impl Iterator<Item = &PublicKey> {
+        self.traces.iter().map(|trace| &trace.pk)
+    }
+
+    /// Verifies and inserts a pair, or returns error if the signer is already in session or the
+    /// signature is invalid.
+    pub fn insert(&mut self, pk: PublicKey, sig: Signature) -> Result<(), SessionError> {
+        let Entry::Vacant(entry) = self.indices.entry(signer_key(&pk)) else {
+            return Err(SessionError::DuplicatedSigner);
+        };
+        Poseidon2TargetSum::verify(self.epoch, self.msg, pk, sig)
+            .map_err(SessionError::InvalidSignature)?;
+        entry.insert(self.traces.len());
+        self.traces.push(VerificationTrace::generate(
+            self.epoch,
+            self.encoded_msg,
+            pk,
+            sig,
+        ));
+        Ok(())
+    }
+
+    /// Removes the pair of the signer and returns its signature, or `None` if the signer is not
+    /// in session.
+    pub fn remove(&mut self, pk: &PublicKey) -> Option<Signature> {
+        let idx = self.indices.remove(&signer_key(pk))?;
+        let trace = self.traces.swap_remove(idx);
+        if let Some(moved) = self.traces.get(idx) {
+            self.indices.insert(signer_key(&moved.pk), idx);
+        }
+        Some(trace.sig)
+    }
+
+    /// Verifies and replaces the signature of the signer and returns the old one, or returns error
+    /// if the signer is not in session or the signature is invalid.
+    pub fn replace(&mut self, pk: PublicKey, sig: Signature) -> Result<Signature, SessionError> {
+        let idx = *self
+            .indices
+            .get(&signer_key(&pk))
+            .ok_or(SessionError::UnknownSigner)?;
+        Poseidon2TargetSum::verify(self.epoch, self.msg, pk, sig)
+            .map_err(SessionError::InvalidSignature)?;
+        let trace = VerificationTrace::generate(self.epoch, self.encoded_msg, pk, sig);
+        Ok(core::mem::replace(&mut self.traces[idx], trace).sig)
+    }
+
+    /// Returns prover inputs of all pairs in session.
+    pub fn finalize(self, extra_capacity_bits: usize) -> Vec<ProverInput<F, HashSigAggAir>> {
+        generate_prover_inputs_from_traces(
+            extra_capacity_bits,
+            self.epoch,
+            self.encoded_msg,
+            &self.traces,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        hash_sig::signer_commitment,
+        session::{AggregationSession, SessionError},
+    };
+    use hash_sig_testdata::mock_vi;
+    use p3_air_ext::check_constraints;
+
+    #[test]
+    fn session() {
+        let vi = mock_vi(8);
+        let mut session = AggregationSession::new(vi.epoch, vi.msg);
+        for (pk, sig) in &vi.pairs {
+            session.insert(*pk, *sig).unwrap();
+        }
+        assert_eq!(
+            session.insert(vi.pairs[0].0, vi.pairs[0].1),
+            Err(SessionError::DuplicatedSigner)
+        );
+
+        let (pk, sig) = vi.pairs[1];
+        let mut invalid = sig;
+        invalid.rho = vi.pairs[2].1.rho;
+        assert!(matches!(
+            session.replace(pk, invalid),
+            Err(SessionError::InvalidSignature(_))
+        ));
+        session.replace(pk, sig).unwrap();
+
+        assert!(session.remove(&vi.pairs[0].0).is_some());
+        assert!(session.remove(&vi.pairs[0].0).is_none());
+        assert_eq!(session.len(), 7);
+        assert!(vi.pairs[1..].iter().all(|(pk, _)| session.contains(pk)));
+
+        let signer_commitment = signer_commitment(session.signers());
+        let prover_inputs = session.finalize(0);
+        check_constraints(&prover_inputs);
+        let main = prover_inputs
+            .iter()
+            .find(|prover_input| prover_input.air().name() == "Main")
+            .unwrap();
+        assert_eq!(main.public_values(), signer_commitment);
+    }
+}
@@ -0,0 +1,171 @@
+//! SSZ container of an aggregate proof:
+//!
+//! ```text
+//! AggregateProof {
+//!     epoch: uint32,
+//!     msg: Vector[uint8, MSG_LEN],
+//!     signers: List[PublicKey, MAX_SIGNERS],
+//!     proof: List[uint8, MAX_PROOF_LEN],
+//! }
+//! ```
+//!
+//! where `proof` is the serialized proof of the proving backend, e.g. `bincode` of the univariate
+//! STARK proof.
+
+use crate::hash_sig::{MSG_LEN, PublicKey};
+use hash_sig_verifier::ssz::{BYTES_PER_CHUNK, Reader, Root, Ssz, merkleize, mix_in_length, pack};
+
+pub const MAX_SIGNERS: usize = 1 << 20;
+pub const MAX_PROOF_LEN: usize = 1 << 30;
+
+const FIXED_PART_LEN: usize = 4 + MSG_LEN + 4 + 4;
+const PK_LEN: usize = PublicKey::FIXED_LEN.unwrap();
+
+// Offsets are encoded as `uint32`.
+const _: () = assert!(FIXED_PART_LEN + MAX_SIGNERS * PK_LEN <= u32::MAX as usize);
+
+#[derive(Clone, Debug)]
+pub struct AggregateProof {
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    signers: Vec<PublicKey>,
+    proof: Vec<u8>,
+}
+
+impl AggregateProof {
+    /// Returns aggregate proof, and fails if `signers` or `proof` exceeds [`MAX_SIGNERS`] or
+    /// [`MAX_PROOF_LEN`].
+    pub fn new(
+        epoch: u32,
+        msg: [u8; MSG_LEN],
+        signers: Vec<PublicKey>,
+        proof: Vec<u8>,
+    ) -> Result<Self, String> {
+        if signers.len() > MAX_SIGNERS {
+            return Err("Invalid signers length".to_string());
+        }
+        if proof.len() > MAX_PROOF_LEN {
+            return Err("Invalid proof length".to_string());
+        }
+        Ok(Self {
+            epoch,
+            msg,
+            signers,
+            proof,
+        })
+    }
+
+    pub const fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    pub const fn msg(&self) -> [u8; MSG_LEN] {
+        self.msg
+    }
+
+    pub fn signers(&self) -> &[PublicKey] {
+        &self.signers
+    }
+
+    pub fn proof(&self) -> &[u8] {
+        &self.proof
+    }
+}
+
+impl Ssz for AggregateProof {
+    const FIXED_LEN: Option<usize> = None;
+
+    fn ssz_append(&self, bytes: &mut Vec<u8>) {
+        let signers_offset = FIXED_PART_LEN as u32;
+        let proof_offset = signers_offset + (self.signers.len() * PK_LEN) as u32;
+        bytes.extend(self.epoch.to_le_bytes());
+        bytes.extend(self.msg);
+        bytes.extend(signers_offset.to_le_bytes());
+        bytes.extend(proof_offset.to_le_bytes());
+        self.signers.iter().for_each(|pk| pk.ssz_append(bytes));
+        bytes.extend(&self.proof);
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let fixed_part = bytes
+            .get(..FIXED_PART_LEN)
+            .ok_or_else(|| "Unexpected end of encoding".to_string())?;
+        let mut reader = Reader::new(fixed_part, FIXED_PART_LEN)?;
+        let epoch = reader.u32()?;
+        let msg = reader.bytes()?;
+        let signers_offset = reader.u32()? as usize;
+        let proof_offset = reader.u32()? as usize;
+        if signers_offset != FIXED_PART_LEN
+            || proof_offset < signers_offset
+            || proof_offset > bytes.len()
+        {
+            return Err("Invalid offsets".to_string());
+        }
+
+        let signers_bytes = &bytes[signers_offset..proof_offset];
+        if signers_bytes.len() % PK_LEN != 0 {
+            return Err("Invalid signers length".to_string());
+        }
+        let signers = signers_bytes
+            .chunks(PK_LEN)
+            .map(PublicKey::from_ssz_bytes)
+            .collect::<Result<_, _>>()?;
+        Self::new(epoch, msg, signers, bytes[proof_offset..].to_vec())
+    }
+
+    fn hash_tree_root(&self) -> Root {
+        let mut epoch = Root::default();
+        epoch[..4].copy_from_slice(&self.epoch.to_le_bytes());
+        let signers = self
+            .signers
+            .iter()
+            .map(Ssz::hash_tree_root)
+            .collect::<Vec<_>>();
+        merkleize(
+            &[
+                epoch,
+                merkleize(&pack(&self.msg), MSG_LEN.div_ceil(BYTES_PER_CHUNK)),
+                mix_in_length(&merkleize(&signers, MAX_SIGNERS), signers.len()),
+                mix_in_length(
+                    &merkleize(&pack(&self.proof), MAX_PROOF_LEN / BYTES_PER_CHUNK),
+                    self.proof.len(),
+                ),
+            ],
+            4,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        hash_sig::VerificationInput,
+        ssz::{AggregateProof, MAX_SIGNERS},
+    };
+    use hash_sig_testdata::mock_vi_with_seed;
+    use hash_sig_verifier::ssz::Ssz;
+
+    #[test]
+    fn round_trip() {
+        let vi: VerificationInput = mock_vi_with_seed(4, 0);
+        let signers = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+        let proof =
+            AggregateProof::new(vi.epoch, vi.msg, signers.clone(), (0..100).collect()).unwrap();
+        let bytes = proof.to_ssz_bytes();
+        let decoded = AggregateProof::from_ssz_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_ssz_bytes(), bytes);
+        assert_eq!(decoded.hash_tree_root(), proof.hash_tree_root());
+
+        let other =
+            AggregateProof::new(vi.epoch, vi.msg, signers.clone(), (0..99).collect()).unwrap();
+        assert_ne!(other.hash_tree_root(), proof.hash_tree_root());
+
+        let mut invalid = bytes;
+        invalid[4 + 32..4 + 32 + 4].copy_from_slice(&0u32.to_le_bytes());
+        assert!(AggregateProof::from_ssz_bytes(&invalid).is_err());
+        assert!(AggregateProof::from_ssz_bytes(&[0; 8]).is_err());
+
+        let too_many_signers = vec![signers[0]; MAX_SIGNERS + 1];
+        assert!(AggregateProof::new(vi.epoch, vi.msg, too_many_signers, Vec::new()).is_err());
+    }
+}
@@ -3,10 +3,21 @@ use p3_air_ext::{ProverInput, SymbolicAirBuilder, VerifierInput};
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 
+/// Static description of an AIR.
+#[derive(Clone, Copy, Debug)]
+pub struct AirDescriptor {
+    pub name: &'static str,
+    /// Whether trace generation consumes interaction collected by trace generation of other AIRs
+    /// (e.g. range check multiplicities), in which case it's generated after all others.
+    pub consumes_interaction: bool,
+}
+
 pub trait AirInstance<Val> {
     type Air;
     type Interaction: Default + Send + Sync;
 
+    fn descriptor(&self) -> AirDescriptor;
+
     fn air(&self) -> Self::Air;
 
     fn public_values(&self) -> Vec<Val> {
@@ -0,0 +1,152 @@
+use core::mem::offset_of;
+use p3_poseidon2_util::air::Poseidon2Cols;
+
+/// Placeholder of a single cell, so `*Cols<Col>` mirrors layout of `*Cols<T>` for
+/// [`ColumnNames`].
+#[derive(Clone, Copy, Debug)]
+pub struct Col;
+
+/// Names of columns in order of layout, where fields of nested columns are joined by `.` and
+/// elements of arrays are suffixed by `[i]`, e.g. `chain_idx.diff_bits[3]`.
+pub trait ColumnNames {
+    fn push_column_names(prefix: &str, names: &mut Vec<String>);
+
+    fn column_names() -> Vec<String> {
+        let mut names = Vec::new();
+        Self::push_column_names("", &mut names);
+        names
+    }
+}
+
+impl ColumnNames for Col {
+    fn push_column_names(prefix: &str, names: &mut Vec<String>) {
+        names.push(prefix.to_string());
+    }
+}
+
+impl<C: ColumnNames, const N: usize> ColumnNames for [C; N] {
+    fn push_column_names(prefix: &str, names: &mut Vec<String>) {
+        (0..N).for_each(|idx| C::push_column_names(&format!("{prefix}[{idx}]"), names));
+    }
+}
+
+/// Only `inputs` are named, the rest are internal columns of the permutation named by offset.
+impl<
+    const WIDTH: usize,
+    const SBOX_DEGREE: u64,
+    const SBOX_REGISTERS: usize,
+    const HALF_FULL_ROUNDS: usize,
+    const PARTIAL_ROUNDS: usize,
+> ColumnNames
+    for Poseidon2Cols<Col, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>
+{
+    fn push_column_names(prefix: &str, names: &mut Vec<String>) {
+        let width = size_of::<
+            Poseidon2Cols<u8, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+        >();
+        let inputs = offset_of!(
+            Poseidon2Cols<u8, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+            inputs
+        );
+        let inputs_prefix = field_prefix(prefix, "inputs");
+        names.extend((0..width).map(|idx| {
+            if (inputs..inputs + WIDTH).contains(&idx) {
+                format!("{inputs_prefix}[{}]", idx - inputs)
+            } else {
+                format!("{prefix}[{idx}]")
+            }
+        }));
+    }
+}
+
+pub fn field_prefix(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{prefix}.{field}")
+    }
+}
+
+/// Pushes names of columns projected by `field`, with type of the field inferred.
+pub fn push_field_column_names<Cols, C: ColumnNames>(
+    _: impl Fn(&Cols) -> &C,
+    prefix: &str,
+    names: &mut Vec<String>,
+) {
+    C::push_column_names(prefix, names);
+}
+
+/// Implements [`ColumnNames`] for `$cols<Col>` with fields listed in order of declaration, which
+/// is checked against layout of `$cols<u8>`.
+macro_rules! impl_column_names {
+    ($cols:ident $(<$(const $param:ident: $ty:ty),*>)? { $($field:ident),* $(,)? }) => {
+        impl$(<$(const $param: $ty),*>)? $crate::util::column::ColumnNames
+            for $cols<$crate::util::column::Col $($(, $param)*)?>
+        {
+            fn push_column_names(prefix: &str, names: &mut Vec<String>) {
+                let start = names.len();
+                $(
+                    assert_eq!(
+                        names.len() - start,
+                        ::core::mem::offset_of!($cols<u8 $($(, $param)*)?>, $field),
+                        concat!("unordered field `", stringify!($field), "`"),
+                    );
+                    $crate::util::column::push_field_column_names(
+                        |cols: &Self| &cols.$field,
+                        &$crate::util::column::field_prefix(prefix, stringify!($field)),
+                        names,
+                    );
+                )*
+                assert_eq!(
+                    names.len() - start,
+                    size_of::<$cols<u8 $($(, $param)*)?>>(),
+                    "missing fields",
+                );
+            }
+        }
+    };
+}
+
+pub(crate) use impl_column_names;
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        air::{
+            chain::{ChainCols, NUM_CHAIN_COLS},
+            decomposition::{DecompositionCols, NUM_DECOMPOSITION_COLS},
+            main::{MainCols, NUM_MAIN_COLS},
+            merkle_tree::{MerkleTreeCols, NUM_MERKLE_TREE_COLS},
+            range_check::{NUM_RANGE_CHECK_COLS, RangeCheckCols},
+        },
+        util::column::{Col, ColumnNames},
+    };
+    use itertools::Itertools;
+
+    #[test]
+    fn column_names() {
+        for (names, width) in [
+            (ChainCols::<Col>::column_names(), NUM_CHAIN_COLS),
+            (
+                DecompositionCols::<Col>::column_names(),
+                NUM_DECOMPOSITION_COLS,
+            ),
+            (MainCols::<Col>::column_names(), NUM_MAIN_COLS),
+            (MerkleTreeCols::<Col>::column_names(), NUM_MERKLE_TREE_COLS),
+            (RangeCheckCols::<Col>::column_names(), NUM_RANGE_CHECK_COLS),
+        ] {
+            assert_eq!(names.len(), width);
+            assert!(names.iter().all_unique());
+        }
+        let names = ChainCols::<Col>::column_names();
+        assert!(names.contains(&"chain_idx.diff_inv".to_string()));
+        assert!(names.contains(&"sig_step.is_last_step.inv".to_string()));
+        assert!(names.contains(&"perm.inputs[0]".to_string()));
+        let names = MainCols::<Col>::column_names();
+        let is_active = names
+            .iter()
+            .position(|name| name == "is_active.is_active")
+            .unwrap();
+        assert_eq!(names[is_active + 1], "sig_idx");
+    }
+}
@@ -1,5 +1,7 @@
 pub mod air_instance;
+pub mod column;
 pub mod field;
+pub mod trace;
 
 /// A generic trait for types that can be borrowed from a `[T]` slice.
 pub trait AlignBorrow<T>: Sized {
@@ -0,0 +1,50 @@
+use core::mem::MaybeUninit;
+use p3_matrix::dense::RowMajorMatrix;
+
+/// Trace allocated with capacity for `extra_capacity_bits` and left uninitialized until all rows
+/// are written.
+pub struct UninitTrace<F> {
+    vec: Vec<F>,
+    width: usize,
+    height: usize,
+}
+
+impl<F> UninitTrace<F> {
+    pub fn new(extra_capacity_bits: usize, height: usize, width: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity((height * width) << extra_capacity_bits),
+            width,
+            height,
+        }
+    }
+
+    /// Returns rows as `R`.
+    ///
+    /// # Safety
+    ///
+    /// `R` must be `#[repr(C)]` and consist only of `MaybeUninit<F>` (or arrays and `#[repr(C)]`
+    /// structs of them), so every bit pattern of the spare capacity is a valid `R`, and the
+    /// `width` of the trace must be the amount of `MaybeUninit<F>` in `R`.
+    pub unsafe fn rows_mut<R>(&mut self) -> &mut [R] {
+        assert_eq!(size_of::<R>(), self.width * size_of::<MaybeUninit<F>>());
+        let trace = &mut self.vec.spare_capacity_mut()[..self.height * self.width];
+        // SAFETY: `R` is valid for any `MaybeUninit<F>` by the contract of this function.
+        let (prefix, rows, suffix) = unsafe { trace.align_to_mut::<R>() };
+        assert!(prefix.is_empty(), "Alignment should match");
+        assert!(suffix.is_empty(), "Alignment should match");
+        assert_eq!(rows.len(), self.height);
+        rows
+    }
+
+    /// # Safety
+    ///
+    /// All rows returned by [`UninitTrace::rows_mut`] must have been fully written.
+    pub unsafe fn assume_init(mut self) -> RowMajorMatrix<F>
+    where
+        F: Clone + Send + Sync,
+    {
+        // SAFETY: The first `height * width` elements are within capacity and written by caller.
+        unsafe { self.vec.set_len(self.height * self.width) };
+        RowMajorMatrix::new(self.vec, self.width)
+    }
+}
@@ -0,0 +1,310 @@
+//! Dataset file of a [`VerificationInput`], where integers are in little-endian:
+//!
+//! | Field    | Size                | Description                                        |
+//! | -------- | ------------------- | -------------------------------------------------- |
+//! | magic    | 8                   | [`MAGIC`]                                          |
+//! | version  | 2                   | [`VERSION`]                                        |
+//! | flags    | 2                   | Bit 0 is set if pairs are zlib compressed          |
+//! | name_len | 2                   | Length of `name`                                   |
+//! | name     | `name_len`          | [`Instantiation::NAME`]                            |
+//! | epoch    | 4                   |                                                    |
+//! | msg      | [`MSG_LEN`]         |                                                    |
+//! | count    | 8                   | Amount of pairs                                    |
+//! | pairs    | `count` times below | Optionally compressed as a whole                   |
+//! | pair_len | 4                   | Length of `pair`, fixed per instantiation          |
+//! | pair     | `pair_len`          | `bincode` of `(PublicKey, Signature)`              |
+
+use flate2::{Compression, read::ZlibDecoder, write::ZlibEncoder};
+use hash_sig_verifier::{
+    LOG_LIFETIME, MSG_LEN, PublicKey, Signature, VerificationInput, instantiation::Instantiation,
+};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+pub const MAGIC: [u8; 8] = *b"HSIGDATA";
+pub const VERSION: u16 = 1;
+
+const FLAG_COMPRESSED: u16 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub name: String,
+    pub epoch: u32,
+    pub msg: [u8; MSG_LEN],
+    pub count: u64,
+    pub compressed: bool,
+}
+
+impl Header {
+    fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        let name_len = u16::try_from(self.name.len())
+            .map_err(|_| invalid_data("name should be shorter than 2^16 bytes"))?;
+        let flags = if self.compressed { FLAG_COMPRESSED } else { 0 };
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&flags.to_le_bytes())?;
+        writer.write_all(&name_len.to_le_bytes())?;
+        writer.write_all(self.name.as_bytes())?;
+        writer.write_all(&self.epoch.to_le_bytes())?;
+        writer.write_all(&self.msg)?;
+        writer.write_all(&self.count.to_le_bytes())
+    }
+
+    fn read(mut reader: impl Read) -> io::Result<Self> {
+        if read_array(&mut reader)? != MAGIC {
+            return Err(invalid_data("not a dataset file"));
+        }
+        let version = u16::from_le_bytes(read_array(&mut reader)?);
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported version {version}, expected {VERSION}"
+            )));
+        }
+        let flags = u16::from_le_bytes(read_array(&mut reader)?);
+        let mut name = vec![0; u16::from_le_bytes(read_array(&mut reader)?).into()];
+        reader.read_exact(&mut name)?;
+        Ok(Self {
+            name: String::from_utf8(name).map_err(|_| invalid_data("name is not UTF-8"))?,
+            epoch: u32::from_le_bytes(read_array(&mut reader)?),
+            msg: read_array(&mut reader)?,
+            count: u64::from_le_bytes(read_array(&mut reader)?),
+            compressed: flags & FLAG_COMPRESSED != 0,
+        })
+    }
+}
+
+/// Writes `vi` into `writer` in the dataset format, where pairs are compressed if `compressed`.
+pub fn write<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut writer: impl Write,
+    vi: &VerificationInput<I, NUM_CHUNKS>,
+    compressed: bool,
+) -> io::Result<()> {
+    Header {
+        name: I::NAME.to_string(),
+        epoch: vi.epoch,
+        msg: vi.msg,
+        count: vi.pairs.len() as u64,
+        compressed,
+    }
+    .write(&mut writer)?;
+    if compressed {
+        let mut encoder = ZlibEncoder::new(writer, Compression::default());
+        write_pairs(&mut encoder, &vi.pairs)?;
+        encoder.finish()?.flush()
+    } else {
+        write_pairs(&mut writer, &vi.pairs)?;
+        writer.flush()
+    }
+}
+
+fn write_pairs<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut writer: impl Write,
+    pairs: &[(PublicKey<I, NUM_CHUNKS>, Signature<I, NUM_CHUNKS>)],
+) -> io::Result<()> {
+    pairs.iter().try_for_each(|pair| {
+        let bytes = bincode::serialize(pair).map_err(io::Error::other)?;
+        let len = u32::try_from(bytes.len()).map_err(|_| invalid_data("pair is too large"))?;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(&bytes)
+    })
+}
+
+/// Saves `vi` into `path` by [`write`].
+pub fn save<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    path: impl AsRef<Path>,
+    vi: &VerificationInput<I, NUM_CHUNKS>,
+    compressed: bool,
+) -> io::Result<()> {
+    write(BufWriter::new(File::create(path)?), vi, compressed)
+}
+
+/// Loads all pairs of dataset file at `path`.
+pub fn load<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    path: impl AsRef<Path>,
+) -> io::Result<VerificationInput<I, NUM_CHUNKS>> {
+    DatasetReader::open(path)?.into_vi()
+}
+
+/// Streaming reader of dataset, which yields pairs one by one.
+pub struct DatasetReader<I, const NUM_CHUNKS: usize> {
+    header: Header,
+    remaining: u64,
+    pair_len: u32,
+    reader: Box<dyn Read + Send>,
+    _marker: PhantomData<I>,
+}
+
+impl<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize> DatasetReader<I, NUM_CHUNKS> {
+    /// Reads header from `reader`, and fails if the dataset is of other instantiation.
+    pub fn new(mut reader: impl Read + Send + 'static) -> io::Result<Self> {
+        let header = Header::read(&mut reader)?;
+        if header.name != I::NAME {
+            return Err(invalid_data(format!(
+                "dataset is of {} but expected {}",
+                header.name,
+                I::NAME
+            )));
+        }
+        let reader: Box<dyn Read + Send> = if header.compressed {
+            Box::new(ZlibDecoder::new(reader))
+        } else {
+            Box::new(reader)
+        };
+        Ok(Self {
+            remaining: header.count,
+            pair_len: pair_len::<I, NUM_CHUNKS>()?,
+            header,
+            reader,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+
+    pub const fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Reads remaining pairs into a [`VerificationInput`].
+    pub fn into_vi(self) -> io::Result<VerificationInput<I, NUM_CHUNKS>> {
+        let (epoch, msg) = (self.header.epoch, self.header.msg);
+        let pairs = self.collect::<io::Result<_>>()?;
+        Ok(VerificationInput { epoch, msg, pairs })
+    }
+
+    fn read_pair(&mut self) -> io::Result<(PublicKey<I, NUM_CHUNKS>, Signature<I, NUM_CHUNKS>)> {
+        let len = u32::from_le_bytes(read_array(&mut self.reader)?);
+        if len != self.pair_len {
+            return Err(invalid_data(format!(
+                "pair length {len} differs from {}",
+                self.pair_len
+            )));
+        }
+        let mut bytes = vec![0; len as usize];
+        self.reader.read_exact(&mut bytes)?;
+        bincode::deserialize(&bytes).map_err(invalid_data)
+    }
+}
+
+impl<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize> Iterator
+    for DatasetReader<I, NUM_CHUNKS>
+{
+    type Item = io::Result<(PublicKey<I, NUM_CHUNKS>, Signature<I, NUM_CHUNKS>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let pair = self.read_pair();
+        // Stop at the first error since the following bytes can't be located anymore.
+        self.remaining = if pair.is_ok() { self.remaining - 1 } else { 0 };
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Lower bound is 0 since iteration stops early at the first error.
+        (0, usize::try_from(self.remaining).ok())
+    }
+}
+
+/// Returns length of `bincode` of a pair, which is the same for all pairs of an instantiation.
+fn pair_len<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>() -> io::Result<u32> {
+    let pair = (
+        PublicKey::<I, NUM_CHUNKS> {
+            parameter: Default::default(),
+            merkle_root: Default::default(),
+        },
+        Signature::<I, NUM_CHUNKS> {
+            rho: Default::default(),
+            one_time_sig: [Default::default(); NUM_CHUNKS],
+            merkle_siblings: [Default::default(); LOG_LIFETIME],
+        },
+    );
+    let len = bincode::serialized_size(&pair).map_err(io::Error::other)?;
+    u32::try_from(len).map_err(|_| invalid_data("pair is too large"))
+}
+
+fn read_array<const N: usize>(mut reader: impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dataset::{DatasetReader, write},
+        mock_vi_with_seed,
+    };
+    use hash_sig_verifier::{
+        VerificationInput,
+        instantiation::poseidon2::{
+            NUM_CHUNKS, Poseidon2TargetSum, baby_bear_horizon::BabyBearHorizon,
+            koala_bear_horizon::KoalaBearHorizon,
+        },
+    };
+    use std::io::Cursor;
+
+    type I = Poseidon2TargetSum<KoalaBearHorizon>;
+
+    fn record_len(vi: &VerificationInput<I, NUM_CHUNKS>) -> usize {
+        4 + bincode::serialize(&vi.pairs[0]).unwrap().len()
+    }
+
+    fn encode(vi: &VerificationInput<I, NUM_CHUNKS>, compressed: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write(&mut bytes, vi, compressed).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let vi = mock_vi_with_seed::<I, NUM_CHUNKS>(8, 0);
+        for compressed in [false, true] {
+            let reader =
+                DatasetReader::<I, NUM_CHUNKS>::new(Cursor::new(encode(&vi, compressed))).unwrap();
+            assert_eq!(reader.header().count, 8);
+            assert_eq!(reader.header().compressed, compressed);
+            let loaded = reader.into_vi().unwrap();
+            assert_eq!(format!("{vi:?}"), format!("{loaded:?}"));
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let vi = mock_vi_with_seed::<I, NUM_CHUNKS>(2, 0);
+        let bytes = encode(&vi, false);
+
+        let other = DatasetReader::<Poseidon2TargetSum<BabyBearHorizon>, NUM_CHUNKS>::new(
+            Cursor::new(bytes.clone()),
+        );
+        assert!(other.is_err());
+
+        let mut truncated = bytes;
+        truncated.truncate(truncated.len() - 1);
+        let pairs = DatasetReader::<I, NUM_CHUNKS>::new(Cursor::new(truncated))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs[0].is_ok() && pairs[1].is_err());
+
+        let mut oversized = encode(&vi, false);
+        let offset = oversized.len() - 2 * record_len(&vi);
+        oversized[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let pairs = DatasetReader::<I, NUM_CHUNKS>::new(Cursor::new(oversized))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 1);
+        assert!(pairs[0].is_err());
+    }
+}
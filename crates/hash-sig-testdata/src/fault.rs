@@ -0,0 +1,194 @@
+use crate::{mock_pair, mock_vi_with_seed};
+use hash_sig_verifier::{LOG_LIFETIME, VerificationInput, instantiation::Instantiation};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::index};
+
+/// Fault of an invalid pair, where each fault is constructed to fail native verification with a
+/// single known error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// `rho` doesn't encode message into the target sum.
+    WrongTargetSum,
+    /// One-time signature element of `i`-th chain is replaced.
+    CorruptedChain(usize),
+    /// Merkle sibling at `level` is replaced, where level 0 is sibling of the leaf.
+    WrongSibling(usize),
+    /// Parameter of public key is replaced by one that still encodes message into the target sum.
+    MismatchedParameter,
+    /// Pair is signed at the other epoch, with `rho` that also encodes message of the expected
+    /// epoch into the target sum.
+    WrongEpoch(u32),
+    /// Pair is a copy of `j`-th pair, which is valid alone.
+    DuplicatedSigner(usize),
+}
+
+impl Fault {
+    /// Returns the error of native verification, or `None` if the pair is valid alone.
+    pub const fn expected_error(self) -> Option<&'static str> {
+        match self {
+            Self::WrongTargetSum => Some("Unmatched target sum"),
+            Self::CorruptedChain(_)
+            | Self::WrongSibling(_)
+            | Self::MismatchedParameter
+            | Self::WrongEpoch(_) => Some("Unmatched merkle root"),
+            Self::DuplicatedSigner(_) => None,
+        }
+    }
+
+    /// Returns random fault of pair at `epoch`, where duplicated signer is one of `valid`.
+    fn random<const NUM_CHUNKS: usize>(rng: &mut impl Rng, epoch: u32, valid: &[usize]) -> Self {
+        match rng.random_range(0..if valid.is_empty() { 5 } else { 6 }) {
+            0 => Self::WrongTargetSum,
+            1 => Self::CorruptedChain(rng.random_range(0..NUM_CHUNKS)),
+            2 => Self::WrongSibling(rng.random_range(0..LOG_LIFETIME)),
+            3 => Self::MismatchedParameter,
+            4 => Self::WrongEpoch(
+                (epoch + rng.random_range(1..1 << LOG_LIFETIME)) % (1 << LOG_LIFETIME),
+            ),
+            _ => Self::DuplicatedSigner(valid[rng.random_range(0..valid.len())]),
+        }
+    }
+}
+
+/// Verification input with ground truth fault of each pair.
+#[derive(Clone, Debug)]
+pub struct LabeledVi<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize> {
+    pub vi: VerificationInput<I, NUM_CHUNKS>,
+    pub faults: Vec<Option<Fault>>,
+}
+
+/// Returns `size` pairs derived from `seed`, where `num_faults` randomly chosen pairs have a
+/// random fault injected by [`inject_fault`].
+pub fn faulty_vi_with_seed<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    size: usize,
+    num_faults: usize,
+    seed: u64,
+) -> LabeledVi<I, NUM_CHUNKS> {
+    assert!(num_faults <= size, "num_faults should be at most size");
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut vi = mock_vi_with_seed(size, rng.random());
+    let faulty = index::sample(&mut rng, size, num_faults).into_vec();
+    let valid = (0..size)
+        .filter(|idx| !faulty.contains(idx))
+        .collect::<Vec<_>>();
+    let mut faults = vec![None; size];
+    for idx in faulty {
+        let fault = Fault::random::<NUM_CHUNKS>(&mut rng, vi.epoch, &valid);
+        inject_fault(&mut vi, idx, fault, &mut rng);
+        faults[idx] = Some(fault);
+    }
+    LabeledVi { vi, faults }
+}
+
+/// Injects `fault` into `idx`-th pair of `vi`.
+pub fn inject_fault<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    vi: &mut VerificationInput<I, NUM_CHUNKS>,
+    idx: usize,
+    fault: Fault,
+    mut rng: impl Rng,
+) {
+    let (epoch, msg) = (vi.epoch, vi.msg);
+    match fault {
+        Fault::WrongTargetSum => {
+            let (pk, sig) = &mut vi.pairs[idx];
+            sig.rho = loop {
+                let rho = I::random_rho(&mut rng);
+                if I::encode(epoch, msg, pk.parameter, rho).is_err() {
+                    break rho;
+                }
+            };
+        }
+        Fault::CorruptedChain(i) => {
+            let sig = &mut vi.pairs[idx].1;
+            sig.one_time_sig[i] = other_hash::<I, NUM_CHUNKS>(&mut rng, sig.one_time_sig[i]);
+        }
+        Fault::WrongSibling(level) => {
+            let sig = &mut vi.pairs[idx].1;
+            sig.merkle_siblings[level] =
+                other_hash::<I, NUM_CHUNKS>(&mut rng, sig.merkle_siblings[level]);
+        }
+        Fault::MismatchedParameter => {
+            let (pk, sig) = &mut vi.pairs[idx];
+            pk.parameter = loop {
+                let parameter = I::random_parameter(&mut rng);
+                if parameter != pk.parameter && I::encode(epoch, msg, parameter, sig.rho).is_ok() {
+                    break parameter;
+                }
+            };
+        }
+        Fault::WrongEpoch(other) => {
+            assert!(
+                other != epoch && other < 1 << LOG_LIFETIME,
+                "other epoch should be a different epoch within lifetime"
+            );
+            vi.pairs[idx] = mock_pair(&mut rng, other, msg, |parameter, rho| {
+                I::encode(epoch, msg, parameter, rho).is_ok()
+            });
+        }
+        Fault::DuplicatedSigner(j) => vi.pairs[idx] = vi.pairs[j],
+    }
+}
+
+fn other_hash<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut rng: impl Rng,
+    hash: I::Hash,
+) -> I::Hash {
+    loop {
+        let other = I::random_hash(&mut rng);
+        if other != hash {
+            break other;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        fault::{Fault, faulty_vi_with_seed, inject_fault},
+        mock_vi_with_seed,
+    };
+    use hash_sig_verifier::{
+        VerificationInput,
+        instantiation::{
+            Instantiation,
+            poseidon2::{NUM_CHUNKS, Poseidon2TargetSum, koala_bear_horizon::KoalaBearHorizon},
+        },
+    };
+    use rand::{SeedableRng, rngs::StdRng};
+
+    type I = Poseidon2TargetSum<KoalaBearHorizon>;
+
+    fn assert_labeled(vi: &VerificationInput<I, NUM_CHUNKS>, faults: &[Option<Fault>]) {
+        for ((pk, sig), fault) in vi.pairs.iter().zip(faults) {
+            assert_eq!(
+                I::verify(vi.epoch, vi.msg, *pk, *sig).err().as_deref(),
+                fault.and_then(Fault::expected_error),
+                "unexpected verification result of fault {fault:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn labeled() {
+        let labeled = faulty_vi_with_seed::<I, NUM_CHUNKS>(16, 8, 0);
+        assert_eq!(labeled.faults.iter().flatten().count(), 8);
+        assert_labeled(&labeled.vi, &labeled.faults);
+    }
+
+    #[test]
+    fn each_fault() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let vi = mock_vi_with_seed::<I, NUM_CHUNKS>(2, 0);
+        for fault in [
+            Fault::WrongTargetSum,
+            Fault::CorruptedChain(NUM_CHUNKS - 1),
+            Fault::WrongSibling(0),
+            Fault::MismatchedParameter,
+            Fault::WrongEpoch(vi.epoch ^ 1),
+            Fault::DuplicatedSigner(1),
+        ] {
+            let mut vi = vi.clone();
+            inject_fault(&mut vi, 0, fault, &mut rng);
+            assert_labeled(&vi, &[Some(fault), None]);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use core::{array::from_fn, iter::zip};
+use hash_sig_verifier::{
+    LOG_LIFETIME, MSG_LEN, PublicKey, Signature, VerificationInput, instantiation::Instantiation,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Key pair with genuine one-time keys and Merkle tree for epochs `0..1 << log_lifetime`.
+///
+/// The tree is the leftmost subtree of a tree of `LOG_LIFETIME` levels, whose siblings above
+/// the subtree are random, so signatures verify as if from a key of full lifetime.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KeyPair<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize> {
+    pub pk: PublicKey<I, NUM_CHUNKS>,
+    log_lifetime: usize,
+    prf_key: [u8; 32],
+    /// Nodes of the subtree by level, from leaves up to the subtree root.
+    nodes: Vec<Vec<I::Hash>>,
+    /// Siblings from level `log_lifetime` up to the root.
+    top_siblings: Vec<I::Hash>,
+}
+
+impl<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize> KeyPair<I, NUM_CHUNKS> {
+    /// Generates key pair deterministically from `seed`.
+    pub fn generate(log_lifetime: usize, seed: u64) -> Self {
+        assert!(
+            log_lifetime <= LOG_LIFETIME,
+            "log_lifetime should be at most {LOG_LIFETIME}"
+        );
+        let mut rng = StdRng::seed_from_u64(seed);
+        let parameter = I::random_parameter(&mut rng);
+        let prf_key = rng.random();
+        let top_siblings = (log_lifetime..LOG_LIFETIME)
+            .map(|_| I::random_hash(&mut rng))
+            .collect::<Vec<_>>();
+
+        let leaves = (0..1u32 << log_lifetime)
+            .into_par_iter()
+            .map(|epoch| {
+                let starts = chain_starts::<I, NUM_CHUNKS>(prf_key, epoch);
+                let one_time_pk = from_fn(|i| I::chain(epoch, parameter, i as _, 0, starts[i]));
+                I::merkle_leaf(epoch, parameter, one_time_pk)
+            })
+            .collect::<Vec<_>>();
+        let mut nodes = vec![leaves];
+        for level in 1..=log_lifetime {
+            let children = &nodes[level - 1];
+            let parents = children
+                .par_chunks(2)
+                .enumerate()
+                .map(|(index, pair)| {
+                    I::merkle_node(parameter, level as _, index as _, pair[0], pair[1])
+                })
+                .collect();
+            nodes.push(parents);
+        }
+
+        let merkle_root = zip(log_lifetime + 1.., &top_siblings)
+            .fold(nodes[log_lifetime][0], |node, (level, sibling)| {
+                I::merkle_node(parameter, level as _, 0, node, *sibling)
+            });
+        Self {
+            pk: PublicKey {
+                parameter,
+                merkle_root,
+            },
+            log_lifetime,
+            prf_key,
+            nodes,
+            top_siblings,
+        }
+    }
+
+    /// Loads key pair of `seed` from `dir`, or generates and stores it if missing.
+    pub fn load_or_generate(
+        dir: impl AsRef<Path>,
+        log_lifetime: usize,
+        seed: u64,
+    ) -> io::Result<Self> {
+        let path = dir
+            .as_ref()
+            .join(format!("{}-{log_lifetime}-{seed}.bin", I::NAME));
+        if let Ok(file) = File::open(&path) {
+            return bincode::deserialize_from(BufReader::new(file)).map_err(io::Error::other);
+        }
+
+        let key_pair = Self::generate(log_lifetime, seed);
+        fs::create_dir_all(dir)?;
+        // Write to a temporary file first so concurrent callers never read a partial key.
+        let tmp = path.with_extension(format!("{}.tmp", process::id()));
+        bincode::serialize_into(BufWriter::new(File::create(&tmp)?), &key_pair)
+            .map_err(io::Error::other)?;
+        fs::rename(tmp, path)?;
+        Ok(key_pair)
+    }
+
+    pub const fn lifetime(&self) -> u32 {
+        1 << self.log_lifetime
+    }
+
+    /// Signs `msg` at `epoch`, where signing the same epoch again reuses its one-time key.
+    pub fn sign(
+        &self,
+        epoch: u32,
+        msg: [u8; MSG_LEN],
+        mut rng: impl Rng,
+    ) -> Signature<I, NUM_CHUNKS> {
+        assert!(
+            epoch < self.lifetime(),
+            "epoch should be less than lifetime"
+        );
+        let parameter = self.pk.parameter;
+        let (rho, x) = loop {
+            let rho = I::random_rho(&mut rng);
+            if let Ok(x) = I::encode(epoch, msg, parameter, rho) {
+                break (rho, x);
+            }
+        };
+        let starts = chain_starts::<I, NUM_CHUNKS>(self.prf_key, epoch);
+        Signature {
+            rho,
+            one_time_sig: from_fn(|i| {
+                (1..=x[i]).fold(starts[i], |value, k| {
+                    I::chain_step(epoch, parameter, i as _, k, value)
+                })
+            }),
+            merkle_siblings: from_fn(|level| {
+                if level < self.log_lifetime {
+                    self.nodes[level][((epoch >> level) ^ 1) as usize]
+                } else {
+                    self.top_siblings[level - self.log_lifetime]
+                }
+            }),
+        }
+    }
+}
+
+/// Returns starts of chains of one-time secret key of `epoch`.
+fn chain_starts<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut prf_key: [u8; 32],
+    epoch: u32,
+) -> [I::Hash; NUM_CHUNKS] {
+    zip(&mut prf_key, epoch.to_le_bytes()).for_each(|(key, byte)| *key ^= byte);
+    let mut rng = StdRng::from_seed(prf_key);
+    from_fn(|_| I::random_hash(&mut rng))
+}
+
+/// Returns directory of cached key pairs from env `HASH_SIG_TESTDATA_CACHE`, or
+/// `hash-sig-testdata` in the temporary directory.
+pub fn cache_dir() -> PathBuf {
+    env::var_os("HASH_SIG_TESTDATA_CACHE")
+        .map_or_else(|| env::temp_dir().join("hash-sig-testdata"), PathBuf::from)
+}
+
+/// Returns `size` pairs signing `msg` at `epoch` by genuine key pairs of lifetime
+/// `1 << log_lifetime`, where each key pair is derived from its own seed drawn from `seed` in
+/// order and cached in [`cache_dir`].
+pub fn genuine_vi<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    size: usize,
+    log_lifetime: usize,
+    seed: u64,
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+) -> VerificationInput<I, NUM_CHUNKS> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let pairs = (0..size)
+        .map(|_| {
+            let key_pair =
+                KeyPair::<I, NUM_CHUNKS>::load_or_generate(cache_dir(), log_lifetime, rng.random())
+                    .unwrap();
+            (key_pair.pk, key_pair.sign(epoch, msg, &mut rng))
+        })
+        .collect();
+    VerificationInput { epoch, msg, pairs }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::key::{KeyPair, genuine_vi};
+    use hash_sig_verifier::instantiation::{
+        Instantiation,
+        poseidon2::{NUM_CHUNKS, Poseidon2TargetSum, koala_bear_horizon::KoalaBearHorizon},
+    };
+    use rand::{SeedableRng, rngs::StdRng};
+    use std::{env, fs, process};
+
+    type I = Poseidon2TargetSum<KoalaBearHorizon>;
+
+    #[test]
+    fn multi_epoch() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let key_pair = KeyPair::<I, NUM_CHUNKS>::generate(4, 0);
+        for epoch in 0..key_pair.lifetime() {
+            let msg = [epoch as u8; 32];
+            let sig = key_pair.sign(epoch, msg, &mut rng);
+            assert!(I::verify(epoch, msg, key_pair.pk, sig).is_ok());
+            assert!(I::verify(epoch ^ 1, msg, key_pair.pk, sig).is_err());
+
+            // Reusing one-time key for another message still verifies.
+            let reused = key_pair.sign(epoch, [!epoch as u8; 32], &mut rng);
+            assert!(I::verify(epoch, [!epoch as u8; 32], key_pair.pk, reused).is_ok());
+            assert_eq!(sig.merkle_siblings, reused.merkle_siblings);
+        }
+    }
+
+    #[test]
+    fn cache() {
+        let dir = env::temp_dir().join(format!("hash-sig-testdata-{}", process::id()));
+        let generated = KeyPair::<I, NUM_CHUNKS>::load_or_generate(&dir, 3, 1).unwrap();
+        let loaded = KeyPair::<I, NUM_CHUNKS>::load_or_generate(&dir, 3, 1).unwrap();
+        assert_eq!(format!("{generated:?}"), format!("{loaded:?}"));
+        fs::remove_dir_all(dir).unwrap();
+
+        let vi = genuine_vi::<I, NUM_CHUNKS>(2, 3, 0, 5, [0; 32]);
+        assert!(
+            vi.pairs
+                .iter()
+                .all(|(pk, sig)| I::verify(vi.epoch, vi.msg, *pk, *sig).is_ok())
+        );
+    }
+}
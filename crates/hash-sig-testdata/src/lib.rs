@@ -1,47 +1,125 @@
 use core::array::from_fn;
 use hash_sig_verifier::{
-    LOG_LIFETIME, PublicKey, Signature, VerificationInput, instantiation::Instantiation,
+    LOG_LIFETIME, MSG_LEN, PublicKey, Signature, VerificationInput, instantiation::Instantiation,
 };
-use rand::{random, rng};
+use rand::{Rng, SeedableRng, random, rngs::StdRng};
 use rayon::prelude::*;
+use std::env;
 
+pub mod dataset;
+pub mod fault;
+pub mod key;
+
+/// Returns `size` valid pairs with random epoch and message, which differ between calls.
 pub fn mock_vi<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
     size: usize,
 ) -> VerificationInput<I, NUM_CHUNKS> {
-    let epoch = random::<u32>() % (1 << LOG_LIFETIME);
-    let msg = random();
-    let pairs = (0..size)
+    mock_vi_with_seed(size, random())
+}
+
+/// Returns seed from env `MOCK_VI_SEED` or a random one, which is printed so a failed test can be
+/// replayed by setting the env.
+pub fn mock_vi_seed() -> u64 {
+    let seed = env::var("MOCK_VI_SEED").map_or_else(
+        |_| random(),
+        |seed| seed.parse().expect("MOCK_VI_SEED should be u64"),
+    );
+    eprintln!("MOCK_VI_SEED={seed}");
+    seed
+}
+
+/// Returns `size` valid pairs with epoch and message derived from `seed`, which is deterministic
+/// regardless of amount of threads.
+pub fn mock_vi_with_seed<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    size: usize,
+    seed: u64,
+) -> VerificationInput<I, NUM_CHUNKS> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let epoch = rng.random::<u32>() % (1 << LOG_LIFETIME);
+    let msg = rng.random();
+    mock_vi_with_epoch_and_msg(size, rng.random(), epoch, msg)
+}
+
+/// Returns `size` valid pairs for `epoch` and `msg`, where each pair is derived from its own seed
+/// drawn from `seed` in order, so the output is deterministic regardless of amount of threads.
+pub fn mock_vi_with_epoch_and_msg<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    size: usize,
+    seed: u64,
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+) -> VerificationInput<I, NUM_CHUNKS> {
+    assert!(
+        epoch < 1 << LOG_LIFETIME,
+        "epoch should be less than lifetime"
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    let seeds = (0..size).map(|_| rng.random()).collect::<Vec<u64>>();
+    let pairs = seeds
         .into_par_iter()
-        .map(|_| {
-            let mut rng = rng();
-            let mut pk = PublicKey {
-                parameter: I::random_parameter(&mut rng),
-                merkle_root: Default::default(),
-            };
-            let mut sig = Signature {
-                rho: Default::default(),
-                one_time_sig: from_fn(|_| I::random_hash(&mut rng)),
-                merkle_siblings: from_fn(|_| I::random_hash(&mut rng)),
-            };
-            (sig.rho, pk.merkle_root) = {
-                let (x, rho) = loop {
-                    let rho = I::random_rho(&mut rng);
-                    if let Ok(x) = I::encode(epoch, msg, pk.parameter, rho) {
-                        break (x, rho);
-                    }
-                };
-
-                let one_time_pk: [I::Hash; NUM_CHUNKS] =
-                    from_fn(|i| I::chain(epoch, pk.parameter, i as _, x[i], sig.one_time_sig[i]));
-
-                (
-                    rho,
-                    I::merkle_root(epoch, pk.parameter, one_time_pk, sig.merkle_siblings),
-                )
-            };
-            I::verify(epoch, msg, pk, sig).unwrap();
-            (pk, sig)
-        })
+        .map(|seed| mock_pair(StdRng::seed_from_u64(seed), epoch, msg, |_, _| true))
         .collect::<Vec<_>>();
     VerificationInput { epoch, msg, pairs }
 }
+
+/// Returns valid pair for `epoch` and `msg` with random Merkle siblings, whose parameter and
+/// `rho` are also accepted by `accept`.
+fn mock_pair<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut rng: impl Rng,
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    accept: impl Fn(I::Parameter, I::Rho) -> bool,
+) -> (PublicKey<I, NUM_CHUNKS>, Signature<I, NUM_CHUNKS>) {
+    let mut pk = PublicKey {
+        parameter: I::random_parameter(&mut rng),
+        merkle_root: Default::default(),
+    };
+    let mut sig = Signature {
+        rho: Default::default(),
+        one_time_sig: from_fn(|_| I::random_hash(&mut rng)),
+        merkle_siblings: from_fn(|_| I::random_hash(&mut rng)),
+    };
+    let x = loop {
+        let rho = I::random_rho(&mut rng);
+        let x = I::encode(epoch, msg, pk.parameter, rho).ok();
+        if let Some(x) = x.filter(|_| accept(pk.parameter, rho)) {
+            sig.rho = rho;
+            break x;
+        }
+    };
+    let one_time_pk = from_fn(|i| I::chain(epoch, pk.parameter, i as _, x[i], sig.one_time_sig[i]));
+    pk.merkle_root = I::merkle_root(epoch, pk.parameter, one_time_pk, sig.merkle_siblings);
+    I::verify(epoch, msg, pk, sig).unwrap();
+    (pk, sig)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{mock_vi_with_epoch_and_msg, mock_vi_with_seed};
+    use hash_sig_verifier::{
+        VerificationInput,
+        instantiation::poseidon2::{
+            NUM_CHUNKS, Poseidon2TargetSum, koala_bear_horizon::KoalaBearHorizon,
+        },
+    };
+    use rayon::ThreadPoolBuilder;
+
+    type Vi = VerificationInput<Poseidon2TargetSum<KoalaBearHorizon>, NUM_CHUNKS>;
+
+    #[test]
+    fn deterministic() {
+        let vi = |num_threads| {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            let vi: Vi = pool.install(|| mock_vi_with_seed(8, 42));
+            format!("{vi:?}")
+        };
+        assert_eq!(vi(1), vi(4));
+
+        let lhs: Vi = mock_vi_with_epoch_and_msg(4, 0, 7, [1; 32]);
+        let rhs: Vi = mock_vi_with_epoch_and_msg(4, 1, 7, [1; 32]);
+        assert_eq!((lhs.epoch, lhs.msg), (7, [1; 32]));
+        assert_ne!(format!("{:?}", lhs.pairs), format!("{:?}", rhs.pairs));
+    }
+}
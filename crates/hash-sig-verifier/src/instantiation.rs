@@ -1,5 +1,7 @@
 use crate::{LOG_LIFETIME, MSG_LEN, PublicKey, Signature};
-use core::{array::from_fn, fmt::Debug};
+use alloc::string::{String, ToString};
+use core::{array::from_fn, fmt::Debug, iter::zip};
+#[cfg(feature = "rand")]
 use rand::Rng;
 use serde::{Serialize, de::DeserializeOwned};
 
@@ -37,10 +39,16 @@ pub trait Instantiation<const NUM_CHUNKS: usize>:
         + Serialize
         + DeserializeOwned;
 
+    /// Identifier of instantiation, e.g. stored in dataset files to reject mismatched ones.
+    const NAME: &'static str;
+
+    #[cfg(feature = "rand")]
     fn random_parameter(rng: impl Rng) -> Self::Parameter;
 
+    #[cfg(feature = "rand")]
     fn random_hash(rng: impl Rng) -> Self::Hash;
 
+    #[cfg(feature = "rand")]
     fn random_rho(rng: impl Rng) -> Self::Rho;
 
     fn encode(
@@ -50,20 +58,65 @@ pub trait Instantiation<const NUM_CHUNKS: usize>:
         rho: Self::Rho,
     ) -> Result<[u16; NUM_CHUNKS], String>;
 
+    /// Exclusive upper bound of positions of a hash chain, where the one-time public key is at
+    /// position `CHAIN_LEN - 1`.
+    const CHAIN_LEN: u16;
+
+    /// Hashes `value` at position `k - 1` of `i`-th chain into position `k`.
+    fn chain_step(
+        epoch: u32,
+        parameter: Self::Parameter,
+        i: u16,
+        k: u16,
+        value: Self::Hash,
+    ) -> Self::Hash;
+
+    /// Hashes one-time public key into leaf of `epoch`.
+    fn merkle_leaf(
+        epoch: u32,
+        parameter: Self::Parameter,
+        one_time_pk: [Self::Hash; NUM_CHUNKS],
+    ) -> Self::Hash;
+
+    /// Hashes children into `index`-th node at `level`, where leaves are at level 0.
+    fn merkle_node(
+        parameter: Self::Parameter,
+        level: u8,
+        index: u32,
+        left: Self::Hash,
+        right: Self::Hash,
+    ) -> Self::Hash;
+
     fn chain(
         epoch: u32,
         parameter: Self::Parameter,
         i: u16,
         x_i: u16,
         one_time_sig_i: Self::Hash,
-    ) -> Self::Hash;
+    ) -> Self::Hash {
+        (x_i + 1..Self::CHAIN_LEN).fold(one_time_sig_i, |value, k| {
+            Self::chain_step(epoch, parameter, i, k, value)
+        })
+    }
 
     fn merkle_root(
         epoch: u32,
         parameter: Self::Parameter,
         one_time_pk: [Self::Hash; NUM_CHUNKS],
         merkle_siblings: [Self::Hash; LOG_LIFETIME],
-    ) -> Self::Hash;
+    ) -> Self::Hash {
+        zip(1.., merkle_siblings).fold(
+            Self::merkle_leaf(epoch, parameter, one_time_pk),
+            |node, (level, sibling)| {
+                let (left, right) = if (epoch >> (level - 1)) & 1 == 0 {
+                    (node, sibling)
+                } else {
+                    (sibling, node)
+                };
+                Self::merkle_node(parameter, level, epoch >> level, left, right)
+            },
+        )
+    }
 
     fn verify(
         epoch: u32,
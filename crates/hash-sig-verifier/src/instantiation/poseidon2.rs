@@ -1,11 +1,15 @@
 use crate::{LOG_LIFETIME, MSG_LEN, concat_array, instantiation::Instantiation};
+use alloc::string::{String, ToString};
 use core::{array::from_fn, fmt::Debug, iter::zip, marker::PhantomData};
 use num_bigint::BigUint;
 use p3_field::PrimeField32;
-use rand::{Rng, distr::StandardUniform, prelude::Distribution};
+#[cfg(feature = "rand")]
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 pub mod baby_bear_horizon;
+#[cfg(feature = "hashsig")]
+pub mod hashsig;
 pub mod koala_bear_horizon;
 
 pub const PARAM_FE_LEN: usize = 5;
@@ -26,24 +30,26 @@ pub const SPONGE_PERM: usize = SPONGE_INPUT_SIZE.div_ceil(SPONGE_RATE);
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Poseidon2TargetSum<P>(PhantomData<P>);
 
-impl<P: Poseidon2Parameter> Instantiation<NUM_CHUNKS> for Poseidon2TargetSum<P>
-where
-    StandardUniform: Distribution<P::F>,
-{
+impl<P: Poseidon2Parameter> Instantiation<NUM_CHUNKS> for Poseidon2TargetSum<P> {
     type Parameter = [P::F; PARAM_FE_LEN];
     type Hash = [P::F; HASH_FE_LEN];
     type Rho = [P::F; RHO_FE_LEN];
 
+    const NAME: &'static str = P::NAME;
+
+    #[cfg(feature = "rand")]
     fn random_parameter(mut rng: impl Rng) -> Self::Parameter {
-        from_fn(|_| rng.random())
+        from_fn(|_| P::random_fe(&mut rng))
     }
 
+    #[cfg(feature = "rand")]
     fn random_hash(mut rng: impl Rng) -> Self::Hash {
-        from_fn(|_| rng.random())
+        from_fn(|_| P::random_fe(&mut rng))
     }
 
+    #[cfg(feature = "rand")]
     fn random_rho(mut rng: impl Rng) -> Self::Rho {
-        from_fn(|_| rng.random())
+        from_fn(|_| P::random_fe(&mut rng))
     }
 
     fn encode(
@@ -65,58 +71,62 @@ where
         Ok(x)
     }
 
-    fn chain(
+    const CHAIN_LEN: u16 = 1 << CHUNK_SIZE;
+
+    fn chain_step(
         epoch: u32,
         parameter: Self::Parameter,
         i: u16,
-        x_i: u16,
-        one_time_sig_i: Self::Hash,
+        k: u16,
+        value: Self::Hash,
     ) -> Self::Hash {
-        (x_i + 1..(1 << CHUNK_SIZE)).fold(one_time_sig_i, |value, k| {
-            const I: usize = PARAM_FE_LEN + TWEAK_FE_LEN + HASH_FE_LEN;
-            P::compress_t16::<I, HASH_FE_LEN>(concat_array![
-                parameter,
-                encode_tweak_chain(epoch, i, k),
-                value
-            ])
-        })
+        const I: usize = PARAM_FE_LEN + TWEAK_FE_LEN + HASH_FE_LEN;
+        P::compress_t16::<I, HASH_FE_LEN>(concat_array![
+            parameter,
+            encode_tweak_chain(epoch, i, k),
+            value
+        ])
     }
 
-    fn merkle_root(
+    fn merkle_leaf(
         epoch: u32,
         parameter: Self::Parameter,
         one_time_pk: [Self::Hash; NUM_CHUNKS],
-        merkle_siblings: [Self::Hash; LOG_LIFETIME],
     ) -> Self::Hash {
-        zip(1.., merkle_siblings).fold(
-            P::sponge::<SPONGE_INPUT_SIZE, HASH_FE_LEN>(concat_array![
-                parameter,
-                encode_tweak_merkle_tree(0, epoch),
-                one_time_pk.into_iter().flatten(),
-            ]),
-            |node, (level, sibling)| {
-                const I: usize = PARAM_FE_LEN + TWEAK_FE_LEN + 2 * HASH_FE_LEN;
-                P::compress_t24::<I, HASH_FE_LEN>(concat_array![
-                    parameter,
-                    encode_tweak_merkle_tree(level, epoch >> level),
-                    (if (epoch >> (level - 1)) & 1 == 0 {
-                        [node, sibling]
-                    } else {
-                        [sibling, node]
-                    })
-                    .into_iter()
-                    .flatten()
-                ])
-            },
-        )
+        P::sponge::<SPONGE_INPUT_SIZE, HASH_FE_LEN>(concat_array![
+            parameter,
+            encode_tweak_merkle_tree(0, epoch),
+            one_time_pk.into_iter().flatten(),
+        ])
+    }
+
+    fn merkle_node(
+        parameter: Self::Parameter,
+        level: u8,
+        index: u32,
+        left: Self::Hash,
+        right: Self::Hash,
+    ) -> Self::Hash {
+        const I: usize = PARAM_FE_LEN + TWEAK_FE_LEN + 2 * HASH_FE_LEN;
+        P::compress_t24::<I, HASH_FE_LEN>(concat_array![
+            parameter,
+            encode_tweak_merkle_tree(level, index),
+            left,
+            right
+        ])
     }
 }
 
 pub trait Poseidon2Parameter: Clone + Copy + Debug + Sized + Send + Sync {
     type F: PrimeField32;
 
+    const NAME: &'static str;
+
     const CAPACITY_VALUES: [Self::F; SPONGE_CAPACITY];
 
+    #[cfg(feature = "rand")]
+    fn random_fe(rng: impl Rng) -> Self::F;
+
     fn permutation_t16(state: [Self::F; 16]) -> [Self::F; 16];
 
     fn permutation_t24(state: [Self::F; 24]) -> [Self::F; 24];
@@ -204,58 +214,47 @@ pub fn decompose<F: PrimeField32, const N: usize>(big: impl Into<BigUint>) -> [F
     })
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "hashsig", feature = "rand"))]
 mod test {
     use crate::{
         LOG_LIFETIME, PublicKey, Signature,
         instantiation::{
             Instantiation,
-            poseidon2::{Poseidon2TargetSum, baby_bear_horizon::BabyBearHorizon},
+            poseidon2::{
+                NUM_CHUNKS, Poseidon2TargetSum, baby_bear_horizon::BabyBearHorizon,
+                hashsig::UpstreamBabyBear,
+            },
         },
+        kat::{self, Kat, NUM_KATS},
     };
-    use core::array::from_fn;
-    use hashsig::signature::{
-        SignatureScheme,
-        generalized_xmss::instantiations_poseidon::lifetime_2_to_the_20::target_sum::SIGTargetSumLifetime20W2NoOff,
-    };
-    use num_bigint::BigUint;
-    use p3_baby_bear::BabyBear;
-    use p3_field::integers::QuotientMap;
+    use hashsig::signature::SignatureScheme;
     use rand_0_8_5::{Rng, thread_rng};
 
     #[test]
     #[ignore = "keygen takes several minutes"]
     fn consistency() {
-        type HashSig = SIGTargetSumLifetime20W2NoOff;
         type HashSigVerifier = Poseidon2TargetSum<BabyBearHorizon>;
 
-        let ark_to_p3 = |v| unsafe {
-            BabyBear::from_canonical_unchecked(u32::try_from(BigUint::from(v)).unwrap())
-        };
-
         let mut rng = thread_rng();
-        let (pk, sk) = HashSig::r#gen(&mut rng);
+        let (pk, sk) = UpstreamBabyBear::r#gen(&mut rng);
+        let converted_pk = PublicKey::<HashSigVerifier, NUM_CHUNKS>::try_from(&pk).unwrap();
+        let mut kats = Vec::new();
         for _ in 0..100 {
             let epoch = rng.gen_range(0..1 << LOG_LIFETIME);
             let msg = rng.r#gen();
-            let sig = HashSig::sign(&mut rng, &sk, epoch, &msg).unwrap();
-            assert!(HashSig::verify(&pk, epoch, &msg, &sig));
-            assert!(
-                HashSigVerifier::verify(
-                    epoch,
-                    msg,
-                    PublicKey {
-                        parameter: pk.parameter().map(ark_to_p3),
-                        merkle_root: pk.root().map(ark_to_p3),
-                    },
-                    Signature {
-                        rho: sig.rho().map(ark_to_p3),
-                        one_time_sig: from_fn(|i| sig.hashes()[i].map(ark_to_p3)),
-                        merkle_siblings: from_fn(|i| sig.path().co_path()[i].map(ark_to_p3)),
-                    }
-                )
-                .is_ok()
-            );
+            let sig = UpstreamBabyBear::sign(&mut rng, &sk, epoch, &msg).unwrap();
+            assert!(UpstreamBabyBear::verify(&pk, epoch, &msg, &sig));
+            let converted_sig = Signature::<HashSigVerifier, NUM_CHUNKS>::try_from(&sig).unwrap();
+            assert!(HashSigVerifier::verify(epoch, msg, converted_pk, converted_sig).is_ok());
+            kats.push(Kat {
+                epoch,
+                msg,
+                pk: converted_pk,
+                sig: converted_sig,
+            });
+        }
+        if kat::update() {
+            kat::store(&kats[..NUM_KATS]);
         }
     }
 }
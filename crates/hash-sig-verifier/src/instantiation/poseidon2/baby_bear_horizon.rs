@@ -4,6 +4,8 @@ use p3_poseidon2_util::instantiation::horizon::baby_bear::{
     poseidon2_baby_bear_horizon_t16, poseidon2_baby_bear_horizon_t24,
 };
 use p3_symmetric::Permutation;
+#[cfg(feature = "rand")]
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -12,12 +14,19 @@ pub struct BabyBearHorizon;
 impl Poseidon2Parameter for BabyBearHorizon {
     type F = BabyBear;
 
+    const NAME: &'static str = "poseidon2-target-sum-baby-bear-horizon";
+
     #[allow(clippy::unreadable_literal)]
     const CAPACITY_VALUES: [BabyBear; SPONGE_CAPACITY] = BabyBear::new_array([
         1812885503, 1176861807, 135926247, 1170849646, 1751547645, 646603316, 1547513893,
         423708400, 961239569,
     ]);
 
+    #[cfg(feature = "rand")]
+    fn random_fe(mut rng: impl Rng) -> BabyBear {
+        rng.random()
+    }
+
     fn permutation_t16(mut state: [BabyBear; 16]) -> [BabyBear; 16] {
         poseidon2_baby_bear_horizon_t16().permute_mut(&mut state);
         state
@@ -0,0 +1,118 @@
+//! Partial, one-directional conversions from public keys and signatures of upstream `hashsig`.
+//!
+//! - Only upstream to local is provided, since upstream keys and signatures have no public
+//!   constructors, so local keys and signatures can't be converted into upstream ones.
+//! - Only Poseidon2 over BabyBear is covered, since the pinned upstream revision has no other
+//!   Poseidon2 instantiation. Conversions are implemented for [`BabyBearHorizon`] by
+//!   `impl_upstream_conversions`, which is expected to be invoked for [`KoalaBearHorizon`] once
+//!   upstream has such instantiation. SHA3 instantiations have no conversions.
+//!
+//! [`KoalaBearHorizon`]: crate::instantiation::poseidon2::koala_bear_horizon::KoalaBearHorizon
+
+use crate::{
+    PublicKey, Signature,
+    instantiation::poseidon2::{
+        NUM_CHUNKS, Poseidon2TargetSum, baby_bear_horizon::BabyBearHorizon,
+    },
+};
+use hashsig::signature::{
+    SignatureScheme,
+    generalized_xmss::instantiations_poseidon::lifetime_2_to_the_20::target_sum::SIGTargetSumLifetime20W2NoOff,
+};
+use num_bigint::BigUint;
+use p3_field::PrimeField32;
+
+/// Upstream scheme of [`Poseidon2TargetSum<BabyBearHorizon>`].
+pub type UpstreamBabyBear = SIGTargetSumLifetime20W2NoOff;
+
+/// Converts upstream field element, and fails if it's not canonical in `F`.
+pub fn fe_from_upstream<F: PrimeField32>(value: impl Into<BigUint>) -> Result<F, String> {
+    let value = value.into();
+    u32::try_from(&value)
+        .ok()
+        .and_then(F::from_canonical_checked)
+        .ok_or_else(|| format!("Field element {value} is out of range"))
+}
+
+/// Converts upstream values by `f`, and fails if there are not exactly `N` values.
+pub fn array_from_upstream<T, U, const N: usize>(
+    values: &[U],
+    f: impl Fn(&U) -> Result<T, String>,
+) -> Result<[T; N], String> {
+    values
+        .iter()
+        .map(f)
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .map_err(|values: Vec<_>| format!("Unexpected length {}, expected {N}", values.len()))
+}
+
+/// Converts upstream field elements, and fails if there are not exactly `N` canonical ones.
+pub fn fes_from_upstream<F: PrimeField32, U: Copy + Into<BigUint>, const N: usize>(
+    values: &[U],
+) -> Result<[F; N], String> {
+    array_from_upstream(values, |value| fe_from_upstream(*value))
+}
+
+/// Implements `TryFrom` upstream public key and signature of `$scheme` for [`PublicKey`] and
+/// [`Signature`] of `Poseidon2TargetSum<$parameter>`.
+macro_rules! impl_upstream_conversions {
+    ($scheme:ty, $parameter:ty) => {
+        impl TryFrom<&<$scheme as SignatureScheme>::PublicKey>
+            for PublicKey<Poseidon2TargetSum<$parameter>, NUM_CHUNKS>
+        {
+            type Error = String;
+
+            fn try_from(pk: &<$scheme as SignatureScheme>::PublicKey) -> Result<Self, String> {
+                Ok(Self {
+                    parameter: fes_from_upstream(&pk.parameter()[..])?,
+                    merkle_root: fes_from_upstream(&pk.root()[..])?,
+                })
+            }
+        }
+
+        impl TryFrom<&<$scheme as SignatureScheme>::Signature>
+            for Signature<Poseidon2TargetSum<$parameter>, NUM_CHUNKS>
+        {
+            type Error = String;
+
+            fn try_from(sig: &<$scheme as SignatureScheme>::Signature) -> Result<Self, String> {
+                Ok(Self {
+                    rho: fes_from_upstream(&sig.rho()[..])?,
+                    one_time_sig: array_from_upstream(&sig.hashes()[..], |hash| {
+                        fes_from_upstream(&hash[..])
+                    })?,
+                    merkle_siblings: array_from_upstream(&sig.path().co_path()[..], |sibling| {
+                        fes_from_upstream(&sibling[..])
+                    })?,
+                })
+            }
+        }
+    };
+}
+
+impl_upstream_conversions!(UpstreamBabyBear, BabyBearHorizon);
+
+#[cfg(test)]
+mod test {
+    use crate::instantiation::poseidon2::hashsig::{fe_from_upstream, fes_from_upstream};
+    use num_bigint::BigUint;
+    use p3_baby_bear::BabyBear;
+    use p3_field::{PrimeCharacteristicRing, PrimeField32};
+
+    #[test]
+    fn fe_range() {
+        let max = BabyBear::ORDER_U32 - 1;
+        assert_eq!(
+            fe_from_upstream::<BabyBear>(BigUint::from(max)),
+            Ok(-BabyBear::ONE)
+        );
+        assert!(fe_from_upstream::<BabyBear>(BigUint::from(BabyBear::ORDER_U32)).is_err());
+        assert!(fe_from_upstream::<BabyBear>(BigUint::from(u64::MAX)).is_err());
+        assert!(fes_from_upstream::<BabyBear, u32, 2>(&[1, 2, 3]).is_err());
+        assert_eq!(
+            fes_from_upstream::<BabyBear, u32, 2>(&[1, 2]),
+            Ok([BabyBear::ONE, BabyBear::TWO])
+        );
+    }
+}
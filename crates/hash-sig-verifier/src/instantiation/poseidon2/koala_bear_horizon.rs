@@ -4,6 +4,8 @@ use p3_poseidon2_util::instantiation::horizon::koala_bear::{
     poseidon2_koala_bear_horizon_t16, poseidon2_koala_bear_horizon_t24,
 };
 use p3_symmetric::Permutation;
+#[cfg(feature = "rand")]
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -12,12 +14,19 @@ pub struct KoalaBearHorizon;
 impl Poseidon2Parameter for KoalaBearHorizon {
     type F = KoalaBear;
 
+    const NAME: &'static str = "poseidon2-target-sum-koala-bear-horizon";
+
     #[allow(clippy::unreadable_literal)]
     const CAPACITY_VALUES: [KoalaBear; SPONGE_CAPACITY] = KoalaBear::new_array([
         556206383, 1483226447, 2129946760, 642552831, 1982893194, 6966942, 872250907, 2081466424,
         1531740321,
     ]);
 
+    #[cfg(feature = "rand")]
+    fn random_fe(mut rng: impl Rng) -> KoalaBear {
+        rng.random()
+    }
+
     fn permutation_t16(mut state: [KoalaBear; 16]) -> [KoalaBear; 16] {
         poseidon2_koala_bear_horizon_t16().permute_mut(&mut state);
         state
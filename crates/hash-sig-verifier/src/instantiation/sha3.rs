@@ -1,5 +1,7 @@
-use crate::{LOG_LIFETIME, MSG_LEN, concat_array, instantiation::Instantiation};
-use core::{array::from_fn, fmt::Debug, iter::zip, marker::PhantomData};
+use crate::{MSG_LEN, concat_array, instantiation::Instantiation};
+use alloc::string::{String, ToString};
+use core::{array::from_fn, fmt::Debug, marker::PhantomData};
+#[cfg(feature = "rand")]
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha3::Digest;
@@ -18,10 +20,14 @@ pub const NUM_CHUNKS: usize = (8 * MSG_HASH_LEN).div_ceil(CHUNK_SIZE);
 pub const TARGET_SUM: u16 = (NUM_CHUNKS + NUM_CHUNKS.div_ceil(2)) as u16;
 
 pub trait Sha3Digest: Debug + Sized + Send + Sync {
+    const NAME: &'static str;
+
     fn sha3_digest<const I: usize, const O: usize>(input: [u8; I]) -> [u8; O];
 }
 
 impl Sha3Digest for Keccak256 {
+    const NAME: &'static str = "sha3-target-sum-keccak256";
+
     fn sha3_digest<const I: usize, const O: usize>(input: [u8; I]) -> [u8; O] {
         let digest = Self::digest(input);
         from_fn(|i| digest[i])
@@ -29,6 +35,8 @@ impl Sha3Digest for Keccak256 {
 }
 
 impl Sha3Digest for Sha3_256 {
+    const NAME: &'static str = "sha3-target-sum-sha3-256";
+
     fn sha3_digest<const I: usize, const O: usize>(input: [u8; I]) -> [u8; O] {
         let digest = Self::digest(input);
         from_fn(|i| digest[i])
@@ -51,14 +59,19 @@ impl<P: Sha3Digest> Instantiation<NUM_CHUNKS> for Sha3TargetSum<P> {
     type Hash = [u8; HASH_LEN];
     type Rho = [u8; RHO_LEN];
 
+    const NAME: &'static str = P::NAME;
+
+    #[cfg(feature = "rand")]
     fn random_parameter(mut rng: impl Rng) -> Self::Parameter {
         rng.random()
     }
 
+    #[cfg(feature = "rand")]
     fn random_hash(mut rng: impl Rng) -> Self::Hash {
         rng.random()
     }
 
+    #[cfg(feature = "rand")]
     fn random_rho(mut rng: impl Rng) -> Self::Rho {
         rng.random()
     }
@@ -83,72 +96,126 @@ impl<P: Sha3Digest> Instantiation<NUM_CHUNKS> for Sha3TargetSum<P> {
         Ok(x)
     }
 
-    fn chain(
+    const CHAIN_LEN: u16 = 1 << CHUNK_SIZE;
+
+    fn chain_step(
         epoch: u32,
         parameter: Self::Parameter,
         i: u16,
-        x_i: u16,
-        one_time_sig_i: Self::Hash,
+        k: u16,
+        value: Self::Hash,
     ) -> Self::Hash {
         const I: usize = PARAM_LEN + TWEAK_CHAIN_LEN + HASH_LEN;
-        (x_i + 1..(1 << CHUNK_SIZE)).fold(one_time_sig_i, |value, step| {
-            P::sha3_digest::<I, HASH_LEN>(concat_array![
-                parameter,
-                encode_tweak_chain(epoch, i, step),
-                value,
-            ])
-        })
+        P::sha3_digest::<I, HASH_LEN>(concat_array![
+            parameter,
+            encode_tweak_chain(epoch, i, k),
+            value,
+        ])
     }
 
-    fn merkle_root(
+    fn merkle_leaf(
         epoch: u32,
         parameter: Self::Parameter,
         one_time_pk: [Self::Hash; NUM_CHUNKS],
-        merkle_siblings: [Self::Hash; LOG_LIFETIME],
     ) -> Self::Hash {
-        zip(1u8.., merkle_siblings).fold(
-            {
-                const I: usize = PARAM_LEN + TWEAK_MERKLE_TREE_LEN + NUM_CHUNKS * HASH_LEN;
-                P::sha3_digest::<I, HASH_LEN>(concat_array![
-                    parameter,
-                    encode_tweak_merkle_tree(0, epoch),
-                    one_time_pk.into_iter().flatten(),
-                ])
-            },
-            |node, (level, sibling)| {
-                const I: usize = PARAM_LEN + TWEAK_MERKLE_TREE_LEN + 2 * HASH_LEN;
-                P::sha3_digest::<I, HASH_LEN>(concat_array![
-                    parameter,
-                    encode_tweak_merkle_tree(level, epoch >> level),
-                    (if (epoch >> (level - 1)) & 1 == 0 {
-                        [node, sibling]
-                    } else {
-                        [sibling, node]
-                    })
-                    .into_iter()
-                    .flatten(),
-                ])
-            },
-        )
+        const I: usize = PARAM_LEN + TWEAK_MERKLE_TREE_LEN + NUM_CHUNKS * HASH_LEN;
+        P::sha3_digest::<I, HASH_LEN>(concat_array![
+            parameter,
+            encode_tweak_merkle_tree(0, epoch),
+            one_time_pk.into_iter().flatten(),
+        ])
+    }
+
+    fn merkle_node(
+        parameter: Self::Parameter,
+        level: u8,
+        index: u32,
+        left: Self::Hash,
+        right: Self::Hash,
+    ) -> Self::Hash {
+        const I: usize = PARAM_LEN + TWEAK_MERKLE_TREE_LEN + 2 * HASH_LEN;
+        P::sha3_digest::<I, HASH_LEN>(concat_array![
+            parameter,
+            encode_tweak_merkle_tree(level, index),
+            left,
+            right,
+        ])
     }
 }
 
-fn encode_tweak_chain(epoch: u32, i: u16, k: u16) -> [u8; 9] {
+pub fn encode_tweak_chain(epoch: u32, i: u16, k: u16) -> [u8; 9] {
     const SEP: u8 = 0x00;
     concat_array![[SEP], epoch.to_be_bytes(), i.to_be_bytes(), k.to_be_bytes()]
 }
 
-fn encode_tweak_merkle_tree(l: u8, i: u32) -> [u8; 6] {
+pub fn encode_tweak_merkle_tree(l: u8, i: u32) -> [u8; 6] {
     const SEP: u8 = 0x01;
     concat_array![[SEP, l], i.to_be_bytes()]
 }
 
-fn encode_tweak_msg(epoch: u32) -> [u8; 5] {
+pub fn encode_tweak_msg(epoch: u32) -> [u8; 5] {
     const SEP: u8 = 0x02;
     concat_array![[SEP], epoch.to_le_bytes()]
 }
 
-fn msg_hash_to_chunks(bytes: [u8; MSG_HASH_LEN]) -> [u16; NUM_CHUNKS] {
+pub fn msg_hash_to_chunks(bytes: [u8; MSG_HASH_LEN]) -> [u16; NUM_CHUNKS] {
     const MASK: u8 = ((1 << CHUNK_SIZE) - 1) as u8;
     from_fn(|i| ((bytes[(i * CHUNK_SIZE) / 8] >> ((i * CHUNK_SIZE) % 8)) & MASK).into())
 }
+
+#[cfg(all(test, feature = "hashsig", feature = "rand"))]
+mod test {
+    use crate::{
+        LOG_LIFETIME, PublicKey, Signature,
+        instantiation::{
+            Instantiation,
+            sha3::{NUM_CHUNKS, Sha3_256, Sha3TargetSum},
+        },
+        kat::{self, Kat, NUM_KATS},
+    };
+    use core::array::from_fn;
+    use hashsig::signature::{
+        SignatureScheme,
+        generalized_xmss::instantiations_sha::lifetime_2_to_the_20::target_sum::SIGTargetSumLifetime20W2NoOff as UpstreamSha3,
+    };
+    use rand_0_8_5::{Rng, thread_rng};
+
+    fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+        bytes.try_into().unwrap()
+    }
+
+    #[test]
+    #[ignore = "keygen takes several minutes"]
+    fn consistency() {
+        type HashSigVerifier = Sha3TargetSum<Sha3_256>;
+
+        let mut rng = thread_rng();
+        let (pk, sk) = UpstreamSha3::r#gen(&mut rng);
+        let converted_pk = PublicKey::<HashSigVerifier, NUM_CHUNKS> {
+            parameter: array(&pk.parameter()[..]),
+            merkle_root: array(&pk.root()[..]),
+        };
+        let mut kats = Vec::new();
+        for _ in 0..100 {
+            let epoch = rng.gen_range(0..1 << LOG_LIFETIME);
+            let msg = rng.r#gen();
+            let sig = UpstreamSha3::sign(&mut rng, &sk, epoch, &msg).unwrap();
+            assert!(UpstreamSha3::verify(&pk, epoch, &msg, &sig));
+            let converted_sig = Signature::<HashSigVerifier, NUM_CHUNKS> {
+                rho: array(&sig.rho()[..]),
+                one_time_sig: from_fn(|i| array(&sig.hashes()[i][..])),
+                merkle_siblings: from_fn(|i| array(&sig.path().co_path()[i][..])),
+            };
+            assert!(HashSigVerifier::verify(epoch, msg, converted_pk, converted_sig).is_ok());
+            kats.push(Kat {
+                epoch,
+                msg,
+                pk: converted_pk,
+                sig: converted_sig,
+            });
+        }
+        if kat::update() {
+            kat::store(&kats[..NUM_KATS]);
+        }
+    }
+}
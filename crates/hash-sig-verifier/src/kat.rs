@@ -0,0 +1,598 @@
+//! Known-answer test vectors per instantiation in `testdata/kat/`:
+//!
+//! - `<NAME>.bin` are signatures as `bincode`. Vectors of instantiations with an upstream
+//!   counterpart are produced by the ignored `consistency` tests when run with `UPDATE_KAT=1` and
+//!   feature `hashsig`, so normal runs check against upstream without keygen. Vectors of the others
+//!   are generated by the local implementation with random Merkle siblings when run with
+//!   `UPDATE_KAT=1`, which then only guard against regressions. Missing vectors fail the tests.
+//! - `<NAME>.json` are inputs and expected outputs of every primitive, for other implementations
+//!   to check bit-exact behavior against. Values are hex of their `bincode` encoding, which is the
+//!   raw bytes for SHA3 and little-endian canonical `uint32` per field element for Poseidon2 (same
+//!   as SSZ), concatenated for arrays, public keys and signatures. They are only rewritten when run
+//!   with `UPDATE_KAT=1`, and missing ones fail the tests.
+
+use crate::{
+    LOG_LIFETIME, MSG_LEN, PublicKey, Signature,
+    instantiation::{
+        Instantiation,
+        poseidon2::{self, MSG_HASH_FE_LEN, Poseidon2Parameter, Poseidon2TargetSum},
+        sha3::{self, Sha3Digest, Sha3TargetSum},
+    },
+};
+use core::{array::from_fn, fmt::Write};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub const NUM_KATS: usize = 8;
+
+const NUM_VECTORS: usize = 4;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Kat<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize> {
+    pub epoch: u32,
+    pub msg: [u8; MSG_LEN],
+    pub pk: PublicKey<I, NUM_CHUNKS>,
+    pub sig: Signature<I, NUM_CHUNKS>,
+}
+
+pub fn path(name: &str, ext: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("testdata/kat/{name}.{ext}"))
+}
+
+/// Returns if vectors should be rewritten.
+pub fn update() -> bool {
+    env::var_os("UPDATE_KAT").is_some()
+}
+
+pub fn load<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>()
+-> Option<Vec<Kat<I, NUM_CHUNKS>>> {
+    let bytes = fs::read(path(I::NAME, "bin")).ok()?;
+    Some(bincode::deserialize(&bytes).expect("KAT vectors should be valid bincode"))
+}
+
+pub fn store<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(kats: &[Kat<I, NUM_CHUNKS>]) {
+    write(&path(I::NAME, "bin"), bincode::serialize(kats).unwrap());
+}
+
+fn write(path: &Path, bytes: impl AsRef<[u8]>) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, bytes).unwrap();
+}
+
+fn mock_kat<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut rng: impl Rng,
+) -> Kat<I, NUM_CHUNKS> {
+    let epoch = rng.random_range(0..1 << LOG_LIFETIME);
+    let msg = rng.random();
+    let parameter = I::random_parameter(&mut rng);
+    let one_time_sig = from_fn(|_| I::random_hash(&mut rng));
+    let merkle_siblings = from_fn(|_| I::random_hash(&mut rng));
+    let (rho, x) = loop {
+        let rho = I::random_rho(&mut rng);
+        if let Ok(x) = I::encode(epoch, msg, parameter, rho) {
+            break (rho, x);
+        }
+    };
+    let one_time_pk = from_fn(|i| I::chain(epoch, parameter, i as _, x[i], one_time_sig[i]));
+    Kat {
+        epoch,
+        msg,
+        pk: PublicKey {
+            parameter,
+            merkle_root: I::merkle_root(epoch, parameter, one_time_pk, merkle_siblings),
+        },
+        sig: Signature {
+            rho,
+            one_time_sig,
+            merkle_siblings,
+        },
+    }
+}
+
+/// Rewrites vectors of `I` by the local implementation if run with `UPDATE_KAT=1`, which is only
+/// expected for instantiations without an upstream counterpart.
+pub fn update_local<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>() {
+    if update() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let kats = (0..NUM_KATS)
+            .map(|_| mock_kat(&mut rng))
+            .collect::<Vec<_>>();
+        store::<I, NUM_CHUNKS>(&kats);
+    }
+}
+
+/// Checks vectors of `I` are accepted and rejected with any flipped message bit, and fails if the
+/// vectors are missing.
+pub fn check<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>() {
+    let kats = load::<I, NUM_CHUNKS>().unwrap_or_else(|| {
+        panic!(
+            "missing {}, see `kat` module documentation to generate",
+            path(I::NAME, "bin").display()
+        )
+    });
+    assert!(!kats.is_empty());
+    for kat in kats {
+        assert_eq!(I::verify(kat.epoch, kat.msg, kat.pk, kat.sig), Ok(()));
+        let mut msg = kat.msg;
+        msg[kat.epoch as usize % MSG_LEN] ^= 1;
+        assert!(I::verify(kat.epoch, msg, kat.pk, kat.sig).is_err());
+    }
+}
+
+/// Helpers of an instantiation outside of [`Instantiation`], with values in hex.
+pub trait Primitives<const NUM_CHUNKS: usize>: Instantiation<NUM_CHUNKS> {
+    fn random_msg_hash(rng: impl Rng) -> String;
+
+    fn msg_hash_to_chunks(hash: &str) -> [u16; NUM_CHUNKS];
+
+    fn encode_tweak_chain(epoch: u32, i: u16, k: u16) -> String;
+
+    fn encode_tweak_merkle_tree(level: u8, index: u32) -> String;
+
+    fn encode_tweak_msg(epoch: u32) -> String;
+}
+
+impl<P: Poseidon2Parameter> Primitives<{ poseidon2::NUM_CHUNKS }> for Poseidon2TargetSum<P> {
+    fn random_msg_hash(mut rng: impl Rng) -> String {
+        to_hex(&from_fn::<P::F, MSG_HASH_FE_LEN, _>(|_| {
+            P::random_fe(&mut rng)
+        }))
+    }
+
+    fn msg_hash_to_chunks(hash: &str) -> [u16; poseidon2::NUM_CHUNKS] {
+        poseidon2::msg_hash_to_chunks::<P::F>(from_hex(hash))
+    }
+
+    fn encode_tweak_chain(epoch: u32, i: u16, k: u16) -> String {
+        to_hex(&poseidon2::encode_tweak_chain::<P::F>(epoch, i, k))
+    }
+
+    fn encode_tweak_merkle_tree(level: u8, index: u32) -> String {
+        to_hex(&poseidon2::encode_tweak_merkle_tree::<P::F>(level, index))
+    }
+
+    fn encode_tweak_msg(epoch: u32) -> String {
+        to_hex(&poseidon2::encode_tweak_msg::<P::F>(epoch))
+    }
+}
+
+impl<P: Sha3Digest> Primitives<{ sha3::NUM_CHUNKS }> for Sha3TargetSum<P> {
+    fn random_msg_hash(mut rng: impl Rng) -> String {
+        to_hex(&rng.random::<[u8; sha3::MSG_HASH_LEN]>())
+    }
+
+    fn msg_hash_to_chunks(hash: &str) -> [u16; sha3::NUM_CHUNKS] {
+        sha3::msg_hash_to_chunks(from_hex(hash))
+    }
+
+    fn encode_tweak_chain(epoch: u32, i: u16, k: u16) -> String {
+        to_hex(&sha3::encode_tweak_chain(epoch, i, k))
+    }
+
+    fn encode_tweak_merkle_tree(level: u8, index: u32) -> String {
+        to_hex(&sha3::encode_tweak_merkle_tree(level, index))
+    }
+
+    fn encode_tweak_msg(epoch: u32) -> String {
+        to_hex(&sha3::encode_tweak_msg(epoch))
+    }
+}
+
+pub fn to_hex(value: &impl Serialize) -> String {
+    bincode::serialize(value)
+        .unwrap()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            write!(hex, "{byte:02x}").unwrap();
+            hex
+        })
+}
+
+pub fn from_hex<T: DeserializeOwned>(hex: &str) -> T {
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).expect("hex should be valid"))
+        .collect::<Vec<_>>();
+    bincode::deserialize(&bytes).expect("hex should be valid encoding")
+}
+
+fn random_hashes<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut rng: impl Rng,
+    n: usize,
+) -> Vec<String> {
+    (0..n).map(|_| to_hex(&I::random_hash(&mut rng))).collect()
+}
+
+fn hashes_from_hex<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize, const N: usize>(
+    hashes: &[String],
+) -> [I::Hash; N] {
+    assert_eq!(hashes.len(), N, "unexpected amount of hashes");
+    from_fn(|i| from_hex(&hashes[i]))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vectors {
+    pub name: String,
+    pub encode: Vec<EncodeVector>,
+    pub msg_hash_to_chunks: Vec<MsgHashToChunksVector>,
+    pub encode_tweak_chain: Vec<TweakChainVector>,
+    pub encode_tweak_merkle_tree: Vec<TweakMerkleTreeVector>,
+    pub encode_tweak_msg: Vec<TweakMsgVector>,
+    pub chain_step: Vec<ChainStepVector>,
+    pub chain: Vec<ChainVector>,
+    pub merkle_leaf: Vec<MerkleLeafVector>,
+    pub merkle_node: Vec<MerkleNodeVector>,
+    pub merkle_root: Vec<MerkleRootVector>,
+    pub verify: Vec<VerifyVector>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodeVector {
+    pub epoch: u32,
+    pub msg: String,
+    pub parameter: String,
+    pub rho: String,
+    pub x: Result<Vec<u16>, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MsgHashToChunksVector {
+    pub hash: String,
+    pub chunks: Vec<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TweakChainVector {
+    pub epoch: u32,
+    pub i: u16,
+    pub k: u16,
+    pub tweak: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TweakMerkleTreeVector {
+    pub level: u8,
+    pub index: u32,
+    pub tweak: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TweakMsgVector {
+    pub epoch: u32,
+    pub tweak: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainStepVector {
+    pub epoch: u32,
+    pub parameter: String,
+    pub i: u16,
+    pub k: u16,
+    pub value: String,
+    pub output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainVector {
+    pub epoch: u32,
+    pub parameter: String,
+    pub i: u16,
+    pub x_i: u16,
+    pub one_time_sig_i: String,
+    pub output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleLeafVector {
+    pub epoch: u32,
+    pub parameter: String,
+    pub one_time_pk: Vec<String>,
+    pub output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleNodeVector {
+    pub parameter: String,
+    pub level: u8,
+    pub index: u32,
+    pub left: String,
+    pub right: String,
+    pub output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleRootVector {
+    pub epoch: u32,
+    pub parameter: String,
+    pub one_time_pk: Vec<String>,
+    pub merkle_siblings: Vec<String>,
+    pub output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyVector {
+    pub epoch: u32,
+    pub msg: String,
+    pub pk: String,
+    pub sig: String,
+    pub result: Result<(), String>,
+}
+
+/// Returns vectors of `I` with random inputs, where outputs are left empty to be filled by
+/// [`evaluate`].
+fn random_vectors<I: Primitives<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    mut rng: impl Rng,
+) -> Vectors {
+    let epochs: [u32; NUM_VECTORS] = from_fn(|_| rng.random_range(0..1 << LOG_LIFETIME));
+    let levels: [u8; NUM_VECTORS] = from_fn(|_| rng.random_range(1..=LOG_LIFETIME as u8));
+    // Genuine signatures, each followed by the one with a flipped message bit.
+    let verify = (0..NUM_VECTORS)
+        .flat_map(|idx| {
+            let kat = mock_kat::<I, NUM_CHUNKS>(&mut rng);
+            let mut flipped = kat.msg;
+            flipped[idx] ^= 1;
+            [kat.msg, flipped].map(|msg| VerifyVector {
+                epoch: kat.epoch,
+                msg: to_hex(&msg),
+                pk: to_hex(&kat.pk),
+                sig: to_hex(&kat.sig),
+                result: Ok(()),
+            })
+        })
+        .collect();
+    Vectors {
+        name: I::NAME.to_string(),
+        encode: epochs
+            .iter()
+            .map(|&epoch| EncodeVector {
+                epoch,
+                msg: to_hex(&rng.random::<[u8; MSG_LEN]>()),
+                parameter: to_hex(&I::random_parameter(&mut rng)),
+                rho: to_hex(&I::random_rho(&mut rng)),
+                x: Ok(Vec::new()),
+            })
+            .collect(),
+        msg_hash_to_chunks: (0..NUM_VECTORS)
+            .map(|_| MsgHashToChunksVector {
+                hash: I::random_msg_hash(&mut rng),
+                chunks: Vec::new(),
+            })
+            .collect(),
+        encode_tweak_chain: epochs
+            .iter()
+            .map(|&epoch| TweakChainVector {
+                epoch,
+                i: rng.random_range(0..NUM_CHUNKS as u16),
+                k: rng.random_range(1..I::CHAIN_LEN),
+                tweak: String::new(),
+            })
+            .collect(),
+        encode_tweak_merkle_tree: levels
+            .iter()
+            .map(|&level| TweakMerkleTreeVector {
+                level,
+                index: rng.random_range(0..1 << (LOG_LIFETIME - level as usize)),
+                tweak: String::new(),
+            })
+            .collect(),
+        encode_tweak_msg: epochs
+            .iter()
+            .map(|&epoch| TweakMsgVector {
+                epoch,
+                tweak: String::new(),
+            })
+            .collect(),
+        chain_step: epochs
+            .iter()
+            .map(|&epoch| ChainStepVector {
+                epoch,
+                parameter: to_hex(&I::random_parameter(&mut rng)),
+                i: rng.random_range(0..NUM_CHUNKS as u16),
+                k: rng.random_range(1..I::CHAIN_LEN),
+                value: to_hex(&I::random_hash(&mut rng)),
+                output: String::new(),
+            })
+            .collect(),
+        chain: epochs
+            .iter()
+            .map(|&epoch| ChainVector {
+                epoch,
+                parameter: to_hex(&I::random_parameter(&mut rng)),
+                i: rng.random_range(0..NUM_CHUNKS as u16),
+                x_i: rng.random_range(0..I::CHAIN_LEN),
+                one_time_sig_i: to_hex(&I::random_hash(&mut rng)),
+                output: String::new(),
+            })
+            .collect(),
+        merkle_leaf: epochs
+            .iter()
+            .map(|&epoch| MerkleLeafVector {
+                epoch,
+                parameter: to_hex(&I::random_parameter(&mut rng)),
+                one_time_pk: random_hashes::<I, NUM_CHUNKS>(&mut rng, NUM_CHUNKS),
+                output: String::new(),
+            })
+            .collect(),
+        merkle_node: levels
+            .iter()
+            .map(|&level| MerkleNodeVector {
+                parameter: to_hex(&I::random_parameter(&mut rng)),
+                level,
+                index: rng.random_range(0..1 << (LOG_LIFETIME - level as usize)),
+                left: to_hex(&I::random_hash(&mut rng)),
+                right: to_hex(&I::random_hash(&mut rng)),
+                output: String::new(),
+            })
+            .collect(),
+        merkle_root: epochs
+            .iter()
+            .map(|&epoch| MerkleRootVector {
+                epoch,
+                parameter: to_hex(&I::random_parameter(&mut rng)),
+                one_time_pk: random_hashes::<I, NUM_CHUNKS>(&mut rng, NUM_CHUNKS),
+                merkle_siblings: random_hashes::<I, NUM_CHUNKS>(&mut rng, LOG_LIFETIME),
+                output: String::new(),
+            })
+            .collect(),
+        verify,
+    }
+}
+
+/// Overwrites outputs of `vectors` by evaluating `I` on their inputs.
+fn evaluate<I: Primitives<NUM_CHUNKS>, const NUM_CHUNKS: usize>(vectors: &mut Vectors) {
+    vectors.encode.iter_mut().for_each(|v| {
+        v.x = I::encode(
+            v.epoch,
+            from_hex(&v.msg),
+            from_hex(&v.parameter),
+            from_hex(&v.rho),
+        )
+        .map(Vec::from);
+    });
+    vectors.msg_hash_to_chunks.iter_mut().for_each(|v| {
+        v.chunks = I::msg_hash_to_chunks(&v.hash).to_vec();
+    });
+    vectors.encode_tweak_chain.iter_mut().for_each(|v| {
+        v.tweak = I::encode_tweak_chain(v.epoch, v.i, v.k);
+    });
+    vectors.encode_tweak_merkle_tree.iter_mut().for_each(|v| {
+        v.tweak = I::encode_tweak_merkle_tree(v.level, v.index);
+    });
+    vectors.encode_tweak_msg.iter_mut().for_each(|v| {
+        v.tweak = I::encode_tweak_msg(v.epoch);
+    });
+    vectors.chain_step.iter_mut().for_each(|v| {
+        v.output = to_hex(&I::chain_step(
+            v.epoch,
+            from_hex(&v.parameter),
+            v.i,
+            v.k,
+            from_hex(&v.value),
+        ));
+    });
+    vectors.chain.iter_mut().for_each(|v| {
+        v.output = to_hex(&I::chain(
+            v.epoch,
+            from_hex(&v.parameter),
+            v.i,
+            v.x_i,
+            from_hex(&v.one_time_sig_i),
+        ));
+    });
+    vectors.merkle_leaf.iter_mut().for_each(|v| {
+        v.output = to_hex(&I::merkle_leaf(
+            v.epoch,
+            from_hex(&v.parameter),
+            hashes_from_hex::<I, NUM_CHUNKS, NUM_CHUNKS>(&v.one_time_pk),
+        ));
+    });
+    vectors.merkle_node.iter_mut().for_each(|v| {
+        v.output = to_hex(&I::merkle_node(
+            from_hex(&v.parameter),
+            v.level,
+            v.index,
+            from_hex(&v.left),
+            from_hex(&v.right),
+        ));
+    });
+    vectors.merkle_root.iter_mut().for_each(|v| {
+        v.output = to_hex(&I::merkle_root(
+            v.epoch,
+            from_hex(&v.parameter),
+            hashes_from_hex::<I, NUM_CHUNKS, NUM_CHUNKS>(&v.one_time_pk),
+            hashes_from_hex::<I, NUM_CHUNKS, LOG_LIFETIME>(&v.merkle_siblings),
+        ));
+    });
+    vectors.verify.iter_mut().for_each(|v| {
+        v.result = I::verify(v.epoch, from_hex(&v.msg), from_hex(&v.pk), from_hex(&v.sig));
+    });
+}
+
+/// Checks outputs of primitive vectors of `I` by evaluating their inputs, and fails if the
+/// vectors are missing. The vectors are only rewritten if run with `UPDATE_KAT=1`.
+pub fn check_primitives<I: Primitives<NUM_CHUNKS>, const NUM_CHUNKS: usize>() {
+    let path = path(I::NAME, "json");
+    if update() {
+        let mut vectors = random_vectors::<I, NUM_CHUNKS>(StdRng::seed_from_u64(0));
+        evaluate::<I, NUM_CHUNKS>(&mut vectors);
+        assert!(vectors.verify.iter().step_by(2).all(|v| v.result.is_ok()));
+        assert!(
+            vectors
+                .verify
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .all(|v| v.result.is_err())
+        );
+        write(
+            &path,
+            serde_json::to_string_pretty(&vectors).unwrap() + "\n",
+        );
+        return;
+    }
+    let json = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "failed to read {}: {err}, run with UPDATE_KAT=1 to generate",
+            path.display()
+        )
+    });
+    let vectors = serde_json::from_str::<Vectors>(&json).expect("vectors should be valid");
+    assert_eq!(vectors.name, I::NAME);
+    let mut evaluated = vectors.clone();
+    evaluate::<I, NUM_CHUNKS>(&mut evaluated);
+    assert!(
+        evaluated == vectors,
+        "outputs differ from {}, rerun with UPDATE_KAT=1 to accept",
+        path.display()
+    );
+}
+
+mod test {
+    use crate::{
+        instantiation::{
+            poseidon2::{
+                self, Poseidon2TargetSum, baby_bear_horizon::BabyBearHorizon,
+                koala_bear_horizon::KoalaBearHorizon,
+            },
+            sha3::{self, Keccak256, Sha3_256, Sha3TargetSum},
+        },
+        kat::{check, check_primitives, update_local},
+    };
+
+    // Vectors are produced by upstream `hashsig`.
+    #[test]
+    fn poseidon2_baby_bear() {
+        check::<Poseidon2TargetSum<BabyBearHorizon>, { poseidon2::NUM_CHUNKS }>();
+        check_primitives::<Poseidon2TargetSum<BabyBearHorizon>, { poseidon2::NUM_CHUNKS }>();
+    }
+
+    // Not checked against upstream, since the pinned `hashsig` has no Poseidon2 over KoalaBear,
+    // so vectors are generated by the local implementation.
+    #[test]
+    fn poseidon2_koala_bear() {
+        update_local::<Poseidon2TargetSum<KoalaBearHorizon>, { poseidon2::NUM_CHUNKS }>();
+        check::<Poseidon2TargetSum<KoalaBearHorizon>, { poseidon2::NUM_CHUNKS }>();
+        check_primitives::<Poseidon2TargetSum<KoalaBearHorizon>, { poseidon2::NUM_CHUNKS }>();
+    }
+
+    // Vectors are produced by upstream `hashsig`.
+    #[test]
+    fn sha3_256() {
+        check::<Sha3TargetSum<Sha3_256>, { sha3::NUM_CHUNKS }>();
+        check_primitives::<Sha3TargetSum<Sha3_256>, { sha3::NUM_CHUNKS }>();
+    }
+
+    // Not checked against upstream, since `hashsig` has no Keccak256 instantiation, so vectors
+    // are generated by the local implementation.
+    #[test]
+    fn keccak256() {
+        update_local::<Sha3TargetSum<Keccak256>, { sha3::NUM_CHUNKS }>();
+        check::<Sha3TargetSum<Keccak256>, { sha3::NUM_CHUNKS }>();
+        check_primitives::<Sha3TargetSum<Keccak256>, { sha3::NUM_CHUNKS }>();
+    }
+}
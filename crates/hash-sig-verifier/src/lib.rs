@@ -1,8 +1,16 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 use crate::instantiation::Instantiation;
+use alloc::vec::Vec;
 use core::fmt::Debug;
 use serde::{Deserialize, Serialize};
 
 pub mod instantiation;
+#[cfg(all(test, feature = "std", feature = "rand"))]
+mod kat;
+pub mod ssz;
 pub mod util;
 
 pub const MSG_LEN: usize = 32;
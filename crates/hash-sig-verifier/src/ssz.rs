@@ -0,0 +1,282 @@
+//! SSZ encoding and hash tree root of [`PublicKey`] and [`Signature`] of
+//! [`Poseidon2TargetSum`], where field elements are encoded as little-endian `uint32`:
+//!
+//! ```text
+//! PublicKey { parameter: Vector[uint32, PARAM_FE_LEN], merkle_root: Vector[uint32, HASH_FE_LEN] }
+//! Signature {
+//!     rho: Vector[uint32, RHO_FE_LEN],
+//!     one_time_sig: Vector[Vector[uint32, HASH_FE_LEN], NUM_CHUNKS],
+//!     merkle_siblings: Vector[Vector[uint32, HASH_FE_LEN], LOG_LIFETIME],
+//! }
+//! ```
+
+use crate::{
+    LOG_LIFETIME, PublicKey, Signature,
+    instantiation::poseidon2::{
+        HASH_FE_LEN, NUM_CHUNKS, PARAM_FE_LEN, Poseidon2Parameter, Poseidon2TargetSum, RHO_FE_LEN,
+    },
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::array::from_fn;
+use p3_field::PrimeField32;
+use sha2::{Digest, Sha256};
+
+pub const BYTES_PER_CHUNK: usize = 32;
+
+pub type Root = [u8; BYTES_PER_CHUNK];
+
+pub trait Ssz: Sized {
+    /// Length of encoding, or `None` if variable-size.
+    const FIXED_LEN: Option<usize>;
+
+    fn ssz_append(&self, bytes: &mut Vec<u8>);
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, String>;
+
+    fn hash_tree_root(&self) -> Root;
+
+    fn to_ssz_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.ssz_append(&mut bytes);
+        bytes
+    }
+}
+
+const PK_LEN: usize = 4 * (PARAM_FE_LEN + HASH_FE_LEN);
+const SIG_LEN: usize = 4 * (RHO_FE_LEN + (NUM_CHUNKS + LOG_LIFETIME) * HASH_FE_LEN);
+
+impl<P: Poseidon2Parameter> Ssz for PublicKey<Poseidon2TargetSum<P>, NUM_CHUNKS> {
+    const FIXED_LEN: Option<usize> = Some(PK_LEN);
+
+    fn ssz_append(&self, bytes: &mut Vec<u8>) {
+        append_fes(bytes, &self.parameter);
+        append_fes(bytes, &self.merkle_root);
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(bytes, PK_LEN)?;
+        Ok(Self {
+            parameter: reader.fes()?,
+            merkle_root: reader.fes()?,
+        })
+    }
+
+    fn hash_tree_root(&self) -> Root {
+        merkleize(&[fes_root(&self.parameter), fes_root(&self.merkle_root)], 2)
+    }
+}
+
+impl<P: Poseidon2Parameter> Ssz for Signature<Poseidon2TargetSum<P>, NUM_CHUNKS> {
+    const FIXED_LEN: Option<usize> = Some(SIG_LEN);
+
+    fn ssz_append(&self, bytes: &mut Vec<u8>) {
+        append_fes(bytes, &self.rho);
+        self.one_time_sig
+            .iter()
+            .chain(&self.merkle_siblings)
+            .for_each(|hash| append_fes(bytes, hash));
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(bytes, SIG_LEN)?;
+        let rho = reader.fes()?;
+        let one_time_sig = reader.array(Reader::fes)?;
+        let merkle_siblings = reader.array(Reader::fes)?;
+        Ok(Self {
+            rho,
+            one_time_sig,
+            merkle_siblings,
+        })
+    }
+
+    fn hash_tree_root(&self) -> Root {
+        let vector_root = |hashes: &[[P::F; HASH_FE_LEN]]| {
+            let roots = hashes.iter().map(|hash| fes_root(hash)).collect::<Vec<_>>();
+            merkleize(&roots, hashes.len())
+        };
+        merkleize(
+            &[
+                fes_root(&self.rho),
+                vector_root(&self.one_time_sig),
+                vector_root(&self.merkle_siblings),
+            ],
+            3,
+        )
+    }
+}
+
+/// Reader of fixed-size encoding.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Returns reader of `bytes`, and fails if it's not `len` bytes.
+    pub fn new(bytes: &'a [u8], len: usize) -> Result<Self, String> {
+        if bytes.len() != len {
+            return Err(format!(
+                "Unexpected encoding length {}, expected {len}",
+                bytes.len()
+            ));
+        }
+        Ok(Self { bytes })
+    }
+
+    pub fn bytes<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let (head, rest) = self
+            .bytes
+            .split_first_chunk()
+            .ok_or_else(|| "Unexpected end of encoding".to_string())?;
+        self.bytes = rest;
+        Ok(*head)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, String> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    pub fn fes<F: PrimeField32, const N: usize>(&mut self) -> Result<[F; N], String> {
+        self.array(|reader| {
+            let value = reader.u32()?;
+            F::from_canonical_checked(value)
+                .ok_or_else(|| format!("Non-canonical field element {value}"))
+        })
+    }
+
+    pub fn array<T: Default, const N: usize>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<[T; N], String> {
+        let mut values = from_fn(|_| T::default());
+        for value in &mut values {
+            *value = f(self)?;
+        }
+        Ok(values)
+    }
+}
+
+pub fn append_fes<F: PrimeField32>(bytes: &mut Vec<u8>, fes: &[F]) {
+    fes.iter()
+        .for_each(|fe| bytes.extend(fe.as_canonical_u32().to_le_bytes()));
+}
+
+/// Returns hash tree root of `Vector[uint32, N]` of `fes`.
+pub fn fes_root<F: PrimeField32>(fes: &[F]) -> Root {
+    let mut bytes = Vec::with_capacity(4 * fes.len());
+    append_fes(&mut bytes, fes);
+    merkleize(&pack(&bytes), (4 * fes.len()).div_ceil(BYTES_PER_CHUNK))
+}
+
+/// Packs `bytes` into chunks, where the last chunk is padded with zeros.
+pub fn pack(bytes: &[u8]) -> Vec<Root> {
+    bytes
+        .chunks(BYTES_PER_CHUNK)
+        .map(|chunk| from_fn(|i| chunk.get(i).copied().unwrap_or_default()))
+        .collect()
+}
+
+/// Merkleizes `chunks` padded with zero chunks to `limit` rounded up to a power of two.
+pub fn merkleize(chunks: &[Root], limit: usize) -> Root {
+    assert!(chunks.len() <= limit, "amount of chunks exceeds limit");
+    let depth = limit.next_power_of_two().trailing_zeros();
+    let mut zero = Root::default();
+    let mut layer = chunks.to_vec();
+    for _ in 0..depth {
+        if layer.len() % 2 == 1 {
+            layer.push(zero);
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash(&pair[0], &pair[1]))
+            .collect();
+        zero = hash(&zero, &zero);
+    }
+    layer.first().copied().unwrap_or(zero)
+}
+
+/// Mixes `len` into `root` of a list.
+pub fn mix_in_length(root: &Root, len: usize) -> Root {
+    let mut len_chunk = Root::default();
+    len_chunk[..8].copy_from_slice(&(len as u64).to_le_bytes());
+    hash(root, &len_chunk)
+}
+
+fn hash(lhs: &Root, rhs: &Root) -> Root {
+    Sha256::new()
+        .chain_update(lhs)
+        .chain_update(rhs)
+        .finalize()
+        .into()
+}
+
+#[cfg(all(test, feature = "rand"))]
+mod test {
+    use crate::{
+        PublicKey, Signature,
+        instantiation::{
+            Instantiation,
+            poseidon2::{NUM_CHUNKS, Poseidon2TargetSum, koala_bear_horizon::KoalaBearHorizon},
+        },
+        ssz::{Root, Ssz, merkleize},
+    };
+    use core::array::from_fn;
+    use rand::{SeedableRng, rngs::StdRng};
+    use sha2::{Digest, Sha256};
+
+    type I = Poseidon2TargetSum<KoalaBearHorizon>;
+
+    #[test]
+    fn round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pk = PublicKey::<I, NUM_CHUNKS> {
+            parameter: I::random_parameter(&mut rng),
+            merkle_root: I::random_hash(&mut rng),
+        };
+        let sig = Signature::<I, NUM_CHUNKS> {
+            rho: I::random_rho(&mut rng),
+            one_time_sig: from_fn(|_| I::random_hash(&mut rng)),
+            merkle_siblings: from_fn(|_| I::random_hash(&mut rng)),
+        };
+
+        let pk_bytes = pk.to_ssz_bytes();
+        assert_eq!(Some(pk_bytes.len()), PublicKey::<I, NUM_CHUNKS>::FIXED_LEN);
+        let decoded = PublicKey::<I, NUM_CHUNKS>::from_ssz_bytes(&pk_bytes).unwrap();
+        assert_eq!(decoded.to_ssz_bytes(), pk_bytes);
+        assert_eq!(decoded.hash_tree_root(), pk.hash_tree_root());
+
+        let sig_bytes = sig.to_ssz_bytes();
+        assert_eq!(Some(sig_bytes.len()), Signature::<I, NUM_CHUNKS>::FIXED_LEN);
+        let decoded = Signature::<I, NUM_CHUNKS>::from_ssz_bytes(&sig_bytes).unwrap();
+        assert_eq!(decoded.to_ssz_bytes(), sig_bytes);
+        assert_eq!(decoded.hash_tree_root(), sig.hash_tree_root());
+
+        assert!(PublicKey::<I, NUM_CHUNKS>::from_ssz_bytes(&pk_bytes[1..]).is_err());
+        let mut non_canonical = pk_bytes;
+        non_canonical[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(PublicKey::<I, NUM_CHUNKS>::from_ssz_bytes(&non_canonical).is_err());
+    }
+
+    #[test]
+    fn merkleize_padding() {
+        let hash = |lhs: &Root, rhs: &Root| -> Root {
+            Sha256::new()
+                .chain_update(lhs)
+                .chain_update(rhs)
+                .finalize()
+                .into()
+        };
+        let (a, b, c) = ([1; 32], [2; 32], [3; 32]);
+        let zero = Root::default();
+        assert_eq!(merkleize(&[a], 1), a);
+        assert_eq!(merkleize(&[a, b], 2), hash(&a, &b));
+        assert_eq!(
+            merkleize(&[a, b, c], 3),
+            hash(&hash(&a, &b), &hash(&c, &zero))
+        );
+        assert_eq!(merkleize(&[], 2), hash(&zero, &zero));
+    }
+}